@@ -1,5 +1,11 @@
 use bevy::prelude::*;
-use shared::protocol::{PlayerId, Position, WorldState};
+use shared::line_of_sight;
+use shared::map::{CellType, GameMap};
+use shared::protocol::{
+    FightPhase, GameEvent, PlayerId, Position, Sequence, Team, WorldDelta, WorldState,
+};
+use shared::spells::{self, SpellEffect, SpellId};
+use shared::summons::{self, Control};
 
 /// Composant représentant un joueur sur la carte
 #[derive(Component)]
@@ -13,29 +19,230 @@ pub struct GridPosition {
     pub position: Position,
 }
 
+/// Nombre maximal d'entrées conservées dans le journal de combat
+pub const COMBAT_LOG_CAPACITY: usize = 50;
+
+/// Durée de l'animation d'un pas, en secondes
+pub const STEP_DURATION: f32 = 0.15;
+
 /// Ressource contenant l'état du monde
 #[derive(Resource, Default)]
 pub struct GameState {
     pub world_state: Option<WorldState>,
     pub my_player_id: Option<PlayerId>,
+    /// Carte du combat, reçue dans le `Welcome`
+    pub map: Option<GameMap>,
+    /// Séquence de `world_state`, base attendue du prochain delta
+    pub sequence: Option<Sequence>,
+    /// Un `Resync` a été demandé et le `Sync` correspondant n'est pas encore arrivé
+    pub resync_pending: bool,
+    /// Derniers événements de combat reçus, du plus ancien au plus récent
+    pub combat_log: Vec<GameEvent>,
+    /// Vainqueurs du combat, une fois celui-ci terminé
+    pub winners: Option<Vec<PlayerId>>,
+    /// Déplacements reçus ou anticipés dont l'animation n'a pas commencé
+    pub pending_moves: Vec<(PlayerId, Vec<Position>)>,
+    /// Emplacement de la barre de sorts survolé, dont la zone d'effet est prévisualisée
+    pub previewed_slot: Option<usize>,
+}
+
+impl GameState {
+    /// Remplace l'état local par un état complet envoyé par le serveur
+    pub fn set_world_state(&mut self, sequence: Sequence, world_state: WorldState) {
+        self.sequence = Some(sequence);
+        self.world_state = Some(world_state);
+        self.resync_pending = false;
+    }
+
+    /// Applique un delta s'il s'enchaîne sur l'état local
+    ///
+    /// Retourne `false` si un état a été manqué : le client doit demander un `Resync`.
+    pub fn apply_delta(&mut self, delta: &WorldDelta) -> bool {
+        match (&mut self.world_state, self.sequence) {
+            (Some(world_state), Some(sequence)) if sequence == delta.base_sequence => {
+                world_state.apply_delta(delta);
+                self.sequence = Some(delta.sequence);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Indique si le joueur local dirige un combattant : son personnage ou une
+    /// invocation qui lui obéit
+    pub fn is_controlled_locally(&self, player_id: PlayerId) -> bool {
+        let Some(my_id) = self.my_player_id else {
+            return false;
+        };
+        player_id == my_id
+            || self
+                .world_state
+                .as_ref()
+                .and_then(|world_state| world_state.get_player(player_id))
+                .and_then(|fighter| fighter.summon)
+                .is_some_and(|summon| {
+                    summon.summoner_id == my_id
+                        && summons::get(summon.creature_id)
+                            .is_some_and(|creature| creature.control == Control::Summoner)
+                })
+    }
+
+    /// Ajoute des événements de combat au journal
+    ///
+    /// L'état lui-même est mis à jour par les deltas qui suivent les événements.
+    /// Les déplacements des autres combattants sont animés case par case ; ceux
+    /// que dirige le joueur local l'ont déjà été quand il les a demandés. Les
+    /// poussées sont animées pour tout le monde.
+    pub fn record_events(&mut self, events: Vec<GameEvent>) {
+        for event in &events {
+            match event {
+                GameEvent::Moved {
+                    player_id, path, ..
+                } if !self.is_controlled_locally(*player_id) && path.len() > 1 => {
+                    self.pending_moves.push((*player_id, path.clone()));
+                }
+                GameEvent::Pushed { player_id, path } if path.len() > 1 => {
+                    self.pending_moves.push((*player_id, path.clone()));
+                }
+                _ => {}
+            }
+        }
+        self.combat_log.extend(events);
+        if self.combat_log.len() > COMBAT_LOG_CAPACITY {
+            let overflow = self.combat_log.len() - COMBAT_LOG_CAPACITY;
+            self.combat_log.drain(..overflow);
+        }
+    }
 }
 
 /// Marqueur pour la carte
 #[derive(Component)]
 pub struct MapTile;
 
+/// Animation d'un joueur le long du chemin qu'il vient de parcourir
+#[derive(Component)]
+pub struct MoveAnimation {
+    path: Vec<Position>,
+    elapsed: f32,
+}
+
+/// Marqueur d'une cellule de départ, affiché pendant le placement
+#[derive(Component)]
+pub struct StartingCellMarker;
+
+/// Marqueur d'une cellule de la zone d'effet prévisualisée
+#[derive(Component)]
+pub struct AreaPreviewMarker;
+
+/// Système pour afficher la zone d'effet du sort survolé dans la barre de sorts
+///
+/// Les marqueurs ne sont recréés que si la zone change.
+pub fn update_area_preview(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    marker_query: Query<Entity, With<AreaPreviewMarker>>,
+    mut shown: Local<Vec<Position>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let cells = game_state
+        .my_player_id
+        .zip(game_state.previewed_slot)
+        .and_then(|(my_id, slot)| spell_area(&game_state, active_fighter(&game_state, my_id), slot))
+        .unwrap_or_default();
+    if cells == *shown {
+        return;
+    }
+    for entity in marker_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    for cell in &cells {
+        let mesh_handle = meshes.add(Plane3d::default().mesh().size(0.9, 0.9));
+        let material_handle = materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 0.6, 0.1, 0.5),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        });
+
+        commands.spawn((
+            AreaPreviewMarker,
+            PbrBundle {
+                mesh: mesh_handle,
+                material: material_handle,
+                transform: Transform::from_xyz(cell.x as f32, 0.02, cell.y as f32),
+                ..default()
+            },
+        ));
+    }
+    *shown = cells;
+}
+
+/// Système pour afficher les cellules de départ des deux équipes pendant le placement
+pub fn update_starting_cells(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    marker_query: Query<Entity, With<StartingCellMarker>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let placement = game_state
+        .world_state
+        .as_ref()
+        .is_some_and(|world_state| world_state.phase == FightPhase::Placement);
+    let Some(map) = game_state.map.as_ref().filter(|_| placement) else {
+        for entity in marker_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+    if !marker_query.is_empty() {
+        return;
+    }
+
+    for (team, color) in [
+        (Team::Red, Color::rgba(0.9, 0.2, 0.2, 0.6)),
+        (Team::Blue, Color::rgba(0.2, 0.3, 0.9, 0.6)),
+    ] {
+        for cell in map.starting_cells(team) {
+            let mesh_handle = meshes.add(Plane3d::default().mesh().size(0.8, 0.8));
+            let material_handle = materials.add(StandardMaterial {
+                base_color: color,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            });
+
+            commands.spawn((
+                StartingCellMarker,
+                PbrBundle {
+                    mesh: mesh_handle,
+                    material: material_handle,
+                    transform: Transform::from_xyz(cell.x as f32, 0.01, cell.y as f32),
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
 /// Système pour mettre à jour l'affichage des joueurs
 pub fn update_players(
     mut commands: Commands,
     game_state: Res<GameState>,
-    mut player_query: Query<(Entity, &Player, &mut Transform)>,
+    mut player_query: Query<(
+        Entity,
+        &Player,
+        &mut Transform,
+        &mut GridPosition,
+        Has<MoveAnimation>,
+    )>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     if let Some(ref world_state) = game_state.world_state {
         // Supprime les joueurs qui n'existent plus
         let current_player_ids: Vec<PlayerId> = world_state.players.iter().map(|p| p.id).collect();
-        for (entity, player, _) in player_query.iter() {
+        for (entity, player, _, _, _) in player_query.iter() {
             if !current_player_ids.contains(&player.id) {
                 commands.entity(entity).despawn();
             }
@@ -44,12 +251,15 @@ pub fn update_players(
         // Ajoute ou met à jour les joueurs
         for player_state in &world_state.players {
             let mut found = false;
-            for (_, player, mut transform) in player_query.iter_mut() {
+            for (_, player, mut transform, mut grid_position, animated) in player_query.iter_mut() {
                 if player.id == player_state.id {
-                    // Met à jour la position
-                    transform.translation.x = player_state.position.x as f32;
-                    transform.translation.z = player_state.position.y as f32;
-                    transform.translation.y = 0.5;
+                    // Met à jour la position, sauf pendant l'animation d'un déplacement
+                    grid_position.position = player_state.position;
+                    if !animated {
+                        transform.translation.x = player_state.position.x as f32;
+                        transform.translation.z = player_state.position.y as f32;
+                        transform.translation.y = 0.5;
+                    }
                     found = true;
                     break;
                 }
@@ -57,18 +267,20 @@ pub fn update_players(
 
             if !found {
                 // Crée un nouveau joueur
-                let color = if Some(player_state.id) == game_state.my_player_id {
-                    Color::rgb(0.0, 1.0, 0.0) // Vert pour le joueur local
+                let mine = Some(player_state.id) == game_state.my_player_id
+                    || player_state.summoner() == game_state.my_player_id;
+                let color = if mine {
+                    Color::rgb(0.0, 1.0, 0.0) // Vert pour le joueur local et ses invocations
                 } else {
                     Color::rgb(1.0, 0.0, 0.0) // Rouge pour les autres
                 };
 
-                // Crée une capsule simple avec mesh de base
-                let mesh_handle = meshes.add(shape::Capsule {
-                    radius: 0.3,
-                    depth: 1.0,
-                    ..default()
-                });
+                // Crée une capsule simple avec mesh de base, plus petite pour une invocation
+                let mesh_handle = if player_state.summon.is_some() {
+                    meshes.add(Capsule3d::new(0.2, 0.5))
+                } else {
+                    meshes.add(Capsule3d::new(0.3, 1.0))
+                };
                 let material_handle = materials.add(StandardMaterial {
                     base_color: color,
                     ..default()
@@ -97,23 +309,52 @@ pub fn update_players(
     }
 }
 
-/// Système pour créer la carte de base
+/// Système pour créer les cellules de la carte, une fois celle-ci reçue du serveur
+///
+/// Les cellules praticables forment un damier, les trous sont sombres et
+/// légèrement enfoncés, les obstacles sont des blocs.
 pub fn setup_map(
     mut commands: Commands,
+    game_state: Res<GameState>,
+    tile_query: Query<(), With<MapTile>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    // Crée une grille 10x10
-    for x in 0..10 {
-        for y in 0..10 {
-            let is_dark = (x + y) % 2 == 0;
-            let color = if is_dark {
-                Color::rgb(0.3, 0.5, 0.3)
-            } else {
-                Color::rgb(0.4, 0.6, 0.4)
-            };
+    let Some(map) = game_state.map.as_ref() else {
+        return;
+    };
+    if !tile_query.is_empty() {
+        return;
+    }
 
-            let mesh_handle = meshes.add(shape::Plane::from_size(1.0));
+    for x in 0..map.width {
+        for y in 0..map.height {
+            let cell = map.cell(Position::new(x, y)).unwrap_or_default();
+            let (mesh_handle, color, height) = match cell {
+                CellType::Walkable => {
+                    let is_dark = (x + y) % 2 == 0;
+                    let color = if is_dark {
+                        Color::rgb(0.3, 0.5, 0.3)
+                    } else {
+                        Color::rgb(0.4, 0.6, 0.4)
+                    };
+                    (
+                        meshes.add(Plane3d::default().mesh().size(1.0, 1.0)),
+                        color,
+                        0.0,
+                    )
+                }
+                CellType::Hole => (
+                    meshes.add(Plane3d::default().mesh().size(1.0, 1.0)),
+                    Color::rgb(0.05, 0.05, 0.08),
+                    -0.2,
+                ),
+                CellType::Obstacle => (
+                    meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+                    Color::rgb(0.45, 0.4, 0.35),
+                    0.5,
+                ),
+            };
             let material_handle = materials.add(StandardMaterial {
                 base_color: color,
                 ..default()
@@ -124,7 +365,7 @@ pub fn setup_map(
                 PbrBundle {
                     mesh: mesh_handle,
                     material: material_handle,
-                    transform: Transform::from_xyz(x as f32, 0.0, y as f32),
+                    transform: Transform::from_xyz(x as f32, height, y as f32),
                     ..default()
                 },
             ));
@@ -132,6 +373,43 @@ pub fn setup_map(
     }
 }
 
+/// Système pour animer les déplacements case par case
+///
+/// Une fois le chemin parcouru, l'animation est retirée et `update_players`
+/// replace le joueur sur sa position.
+pub fn animate_moves(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut game_state: ResMut<GameState>,
+    mut player_query: Query<(Entity, &Player, &mut Transform, Option<&mut MoveAnimation>)>,
+) {
+    for (player_id, path) in game_state.pending_moves.drain(..) {
+        if let Some((entity, ..)) = player_query.iter().find(|(_, p, ..)| p.id == player_id) {
+            commands
+                .entity(entity)
+                .insert(MoveAnimation { path, elapsed: 0.0 });
+        }
+    }
+
+    for (entity, _, mut transform, animation) in player_query.iter_mut() {
+        let Some(mut animation) = animation else {
+            continue;
+        };
+        animation.elapsed += time.delta_seconds();
+        let progress = animation.elapsed / STEP_DURATION;
+        let step = progress as usize;
+        let (Some(from), Some(to)) = (animation.path.get(step), animation.path.get(step + 1))
+        else {
+            commands.entity(entity).remove::<MoveAnimation>();
+            continue;
+        };
+
+        let t = progress.fract();
+        transform.translation.x = from.x as f32 + (to.x - from.x) as f32 * t;
+        transform.translation.z = from.y as f32 + (to.y - from.y) as f32 * t;
+    }
+}
+
 /// Système pour gérer les entrées clavier
 pub fn handle_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
@@ -139,6 +417,8 @@ pub fn handle_input(
     mut network_events: EventWriter<crate::network::NetworkEvent>,
 ) {
     if let Some(my_id) = game_state.my_player_id {
+        // Déplacements, sorts et fin de tour vont à l'invocation dirigée pendant son tour
+        let fighter_id = active_fighter(&game_state, my_id);
         let mut moved = false;
         let mut target_position = None;
 
@@ -146,7 +426,7 @@ pub fn handle_input(
             || keyboard_input.just_pressed(KeyCode::KeyW)
         {
             if let Some(ref world_state) = game_state.world_state {
-                if let Some(player) = world_state.get_player(my_id) {
+                if let Some(player) = world_state.get_player(fighter_id) {
                     target_position = Some(Position::new(player.position.x, player.position.y - 1));
                     moved = true;
                 }
@@ -157,7 +437,7 @@ pub fn handle_input(
             || keyboard_input.just_pressed(KeyCode::KeyS)
         {
             if let Some(ref world_state) = game_state.world_state {
-                if let Some(player) = world_state.get_player(my_id) {
+                if let Some(player) = world_state.get_player(fighter_id) {
                     target_position = Some(Position::new(player.position.x, player.position.y + 1));
                     moved = true;
                 }
@@ -168,7 +448,7 @@ pub fn handle_input(
             || keyboard_input.just_pressed(KeyCode::KeyA)
         {
             if let Some(ref world_state) = game_state.world_state {
-                if let Some(player) = world_state.get_player(my_id) {
+                if let Some(player) = world_state.get_player(fighter_id) {
                     target_position = Some(Position::new(player.position.x - 1, player.position.y));
                     moved = true;
                 }
@@ -179,7 +459,7 @@ pub fn handle_input(
             || keyboard_input.just_pressed(KeyCode::KeyD)
         {
             if let Some(ref world_state) = game_state.world_state {
-                if let Some(player) = world_state.get_player(my_id) {
+                if let Some(player) = world_state.get_player(fighter_id) {
                     target_position = Some(Position::new(player.position.x + 1, player.position.y));
                     moved = true;
                 }
@@ -188,12 +468,171 @@ pub fn handle_input(
 
         if moved {
             if let Some(pos) = target_position {
-                network_events.send(crate::network::NetworkEvent::SendMove(my_id, pos));
+                network_events.send(crate::network::NetworkEvent::SendMove(fighter_id, pos));
             }
         }
 
         if keyboard_input.just_pressed(KeyCode::Space) {
-            network_events.send(crate::network::NetworkEvent::EndTurn(my_id));
+            network_events.send(crate::network::NetworkEvent::EndTurn(fighter_id));
+        }
+
+        // Placement : Tab passe à la cellule de départ suivante, R valide
+        if keyboard_input.just_pressed(KeyCode::Tab) {
+            if let Some(cell) = next_starting_cell(&game_state, my_id) {
+                network_events.send(crate::network::NetworkEvent::Place(my_id, cell));
+            }
+        }
+        if keyboard_input.just_pressed(KeyCode::KeyR) {
+            if let Some(player) = game_state
+                .world_state
+                .as_ref()
+                .and_then(|world_state| world_state.get_player(my_id))
+            {
+                network_events.send(crate::network::NetworkEvent::Ready(my_id, !player.ready));
+            }
+        }
+
+        // Touches de la barre de sorts : lance le sort correspondant
+        for (slot, key) in SPELL_KEYS.iter().enumerate() {
+            if !keyboard_input.just_pressed(*key) {
+                continue;
+            }
+            if let Some((spell_id, target_cell)) = spell_target(&game_state, fighter_id, slot) {
+                network_events.send(crate::network::NetworkEvent::CastSpell(
+                    fighter_id,
+                    spell_id,
+                    target_cell,
+                ));
+            }
         }
     }
 }
+
+/// Touches de la barre de sorts, dans l'ordre des sorts connus
+pub const SPELL_KEYS: [KeyCode; 23] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Digit0,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+];
+
+/// Libellés des touches de la barre de sorts, affichés dans l'interface
+pub const SPELL_KEY_LABELS: [&str; 23] = [
+    "1", "2", "3", "4", "5", "6", "7", "8", "9", "0", "-", "=", "F1", "F2", "F3", "F4", "F5", "F6",
+    "F7", "F8", "F9", "F10", "F11",
+];
+
+/// Cellule de départ de l'équipe du joueur qui suit sa position actuelle
+fn next_starting_cell(game_state: &GameState, my_id: PlayerId) -> Option<Position> {
+    let world_state = game_state.world_state.as_ref()?;
+    if world_state.phase != FightPhase::Placement {
+        return None;
+    }
+    let player = world_state.get_player(my_id)?;
+    let cells = game_state.map.as_ref()?.starting_cells(player.team);
+    let current = cells.iter().position(|&cell| cell == player.position);
+    let next = current.map_or(0, |index| (index + 1) % cells.len());
+    cells.get(next).copied()
+}
+
+/// Choisit la case visée par le sort d'un emplacement de la barre de sorts
+///
+/// Les sorts sans portée minimale se lancent sur soi, les autres sur
+/// l'adversaire vivant le plus proche qui soit à portée et en ligne de vue.
+/// Les téléportations et invocations visent la case libre à portée la plus
+/// proche de l'adversaire le plus proche. Retourne `None` si aucune cible
+/// n'est valide.
+pub fn spell_target(
+    game_state: &GameState,
+    my_id: PlayerId,
+    slot: usize,
+) -> Option<(SpellId, Position)> {
+    let world_state = game_state.world_state.as_ref()?;
+    let map = game_state.map.as_ref()?;
+    let player = world_state.get_player(my_id)?;
+    let spell = spells::get(player.spells.get(slot)?.spell_id)?;
+
+    if spell.min_range == 0 {
+        return Some((spell.id, player.position));
+    }
+
+    let is_occupied = |cell: Position| {
+        world_state
+            .players
+            .iter()
+            .any(|p| p.position == cell && p.is_alive)
+    };
+    let can_target = |cell: Position| {
+        spell.check_range(player.position, cell).is_ok()
+            && (!spell.line_of_sight
+                || line_of_sight::has_line_of_sight(map, player.position, cell, is_occupied))
+    };
+    let enemies = world_state
+        .players
+        .iter()
+        .filter(|p| p.team != player.team && p.is_alive);
+
+    if spell.effects.iter().any(SpellEffect::targets_cell) {
+        let nearest = enemies
+            .min_by_key(|p| p.position.manhattan_distance(&player.position))?
+            .position;
+        return (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| Position::new(x, y)))
+            .filter(|&cell| map.is_walkable(cell) && !is_occupied(cell) && can_target(cell))
+            .min_by_key(|cell| cell.manhattan_distance(&nearest))
+            .map(|cell| (spell.id, cell));
+    }
+
+    enemies
+        .filter(|p| can_target(p.position))
+        .min_by_key(|p| p.position.manhattan_distance(&player.position))
+        .map(|target| (spell.id, target.position))
+}
+
+/// Combattant dirigé par le joueur local : son invocation si c'est à elle de
+/// jouer et qu'elle lui obéit, son personnage sinon
+pub fn active_fighter(game_state: &GameState, my_id: PlayerId) -> PlayerId {
+    match game_state.world_state.as_ref() {
+        Some(world_state) if game_state.is_controlled_locally(world_state.current_turn) => {
+            world_state.current_turn
+        }
+        _ => my_id,
+    }
+}
+
+/// Cases de la carte touchées par le sort d'un emplacement, lancé sur sa cible par défaut
+///
+/// Retourne `None` si le sort n'a aucune cible valide (voir `spell_target`).
+pub fn spell_area(game_state: &GameState, my_id: PlayerId, slot: usize) -> Option<Vec<Position>> {
+    let (spell_id, target_cell) = spell_target(game_state, my_id, slot)?;
+    let map = game_state.map.as_ref()?;
+    let caster = game_state.world_state.as_ref()?.get_player(my_id)?;
+    let spell = spells::get(spell_id)?;
+    Some(
+        spell
+            .area
+            .cells(caster.position, target_cell)
+            .into_iter()
+            .filter(|&cell| map.contains(cell))
+            .collect(),
+    )
+}
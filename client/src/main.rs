@@ -31,11 +31,14 @@ fn main() {
         .add_event::<network::NetworkEvent>()
         .add_systems(Startup, setup_camera)
         .add_systems(Update, ui::main_menu_system.run_if(in_state(AppState::MainMenu)))
-        .add_systems(OnEnter(AppState::InGame), game::setup_map)
         .add_systems(
             Update,
             (
+                game::setup_map,
                 game::update_players,
+                game::animate_moves,
+                game::update_starting_cells,
+                game::update_area_preview,
                 game::handle_input,
                 network::handle_network_events,
                 network::receive_from_server,
@@ -1,33 +1,135 @@
 use bevy::prelude::*;
-use shared::protocol::{Message, PlayerId, Position};
-use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use futures_util::{SinkExt, StreamExt};
+use shared::framing::{MessageCodec, DEFAULT_MAX_FRAME_SIZE};
+use shared::pathfinding;
+use shared::protocol::serialization::WireFormat;
+use shared::protocol::{Message, PlayerId, Position, RequestId, WorldDelta, PROTOCOL_VERSION};
+use shared::spells::SpellId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, watch};
+use tokio_util::codec::{FramedRead, FramedWrite};
 
 /// Événements réseau
 #[derive(Event)]
 pub enum NetworkEvent {
     SendMove(PlayerId, Position),
+    #[allow(dead_code)]
     SendAttack(PlayerId, PlayerId),
+    /// Lanceur, sort et case ciblée
+    CastSpell(PlayerId, SpellId, Position),
     EndTurn(PlayerId),
-    Connected,
-    Disconnected,
+    /// Choix d'une cellule de départ pendant le placement
+    Place(PlayerId, Position),
+    /// Validation ou annulation du placement
+    Ready(PlayerId, bool),
 }
 
 /// Ressource pour la connexion réseau
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct NetworkConnection {
-    pub stream: Option<Arc<Mutex<tokio::net::TcpStream>>>,
+    pub connection: Option<ServerConnection>,
     pub connected: bool,
+    pub pending: PendingRequests,
 }
 
-impl Default for NetworkConnection {
-    fn default() -> Self {
-        Self {
-            stream: None,
-            connected: false,
-        }
+/// Délai au-delà duquel une requête sans réponse est abandonnée
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Requête envoyée au serveur dont la réponse n'est pas encore arrivée
+pub struct PendingRequest {
+    pub message: Message,
+    /// Champs modifiés par l'application optimiste, restaurés si l'action est refusée
+    pub rollback: Option<MoveRollback>,
+    /// Instant d'envoi de la requête
+    pub sent_at: Instant,
+}
+
+/// Position et PM d'un joueur avant un déplacement optimiste
+///
+/// Seuls ces champs sont restaurés : le reste de l'état du joueur a pu être
+/// mis à jour par le serveur entre-temps.
+pub struct MoveRollback {
+    pub player_id: PlayerId,
+    pub position: Position,
+    pub movement_points: u32,
+}
+
+/// Requêtes en attente de réponse, indexées par identifiant de requête
+#[derive(Default)]
+pub struct PendingRequests {
+    next_request_id: RequestId,
+    requests: HashMap<RequestId, PendingRequest>,
+}
+
+impl PendingRequests {
+    /// Enregistre une requête et retourne l'identifiant à envoyer avec elle
+    pub fn insert(&mut self, message: Message, rollback: Option<MoveRollback>) -> RequestId {
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1);
+        self.requests.insert(
+            request_id,
+            PendingRequest {
+                message,
+                rollback,
+                sent_at: Instant::now(),
+            },
+        );
+        request_id
+    }
+
+    /// Retire la requête à laquelle le serveur vient de répondre
+    pub fn complete(&mut self, request_id: RequestId) -> Option<PendingRequest> {
+        self.requests.remove(&request_id)
+    }
+
+    /// Retire les requêtes envoyées depuis plus de `timeout`
+    pub fn expire(&mut self, now: Instant, timeout: Duration) -> Vec<PendingRequest> {
+        let expired: Vec<RequestId> = self
+            .requests
+            .iter()
+            .filter(|(_, request)| now.duration_since(request.sent_at) >= timeout)
+            .map(|(request_id, _)| *request_id)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|request_id| self.requests.remove(&request_id))
+            .collect()
+    }
+
+    /// Parcourt les requêtes en attente
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = (&RequestId, &PendingRequest)> {
+        self.requests.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+}
+
+/// Canaux d'une connexion active avec le serveur
+///
+/// La lecture et l'écriture sont chacune gérées par une tâche Tokio dédiée :
+/// les messages reçus arrivent dans `incoming`, ceux à envoyer passent par `outgoing`.
+pub struct ServerConnection {
+    outgoing: mpsc::UnboundedSender<Message>,
+    incoming: mpsc::UnboundedReceiver<Message>,
+}
+
+impl ServerConnection {
+    /// Met un message en file d'envoi vers le serveur
+    pub fn send(&self, message: Message) -> Result<(), String> {
+        self.outgoing
+            .send(message)
+            .map_err(|_| "Connexion au serveur fermée".to_string())
     }
 }
 
@@ -37,160 +139,389 @@ static TOKIO_RUNTIME: once_cell::sync::Lazy<tokio::runtime::Runtime> =
         tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime")
     });
 
-/// Système pour se connecter au serveur
+/// Variable d'environnement choisissant le format de sérialisation (bincode, json, msgpack)
+const WIRE_FORMAT_ENV: &str = "DOFUS_WIRE_FORMAT";
+
+/// Formats proposés au serveur, par ordre de préférence
+///
+/// Le format demandé via `DOFUS_WIRE_FORMAT` passe en premier s'il est compilé
+/// dans ce build ; bincode reste toujours proposé en repli.
+fn preferred_wire_formats() -> Vec<WireFormat> {
+    let mut formats = Vec::new();
+    if let Ok(value) = std::env::var(WIRE_FORMAT_ENV) {
+        match value.parse::<WireFormat>() {
+            Ok(format) if format.is_available() => formats.push(format),
+            Ok(format) => eprintln!("Format {} non disponible dans ce build", format),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    if !formats.contains(&WireFormat::Bincode) {
+        formats.push(WireFormat::Bincode);
+    }
+    formats
+}
+
+/// Se connecte au serveur et lance les tâches de lecture et d'écriture
+///
+/// Le `Connect` de la poignée de main est le premier message mis en file ;
+/// le serveur y répond par `Welcome` ou `ConnectionRejected`. Le format
+/// annoncé dans le `Welcome` est ensuite utilisé par les deux tâches.
 pub async fn connect_to_server(
     address: &str,
-) -> Result<Arc<Mutex<TcpStream>>, Box<dyn std::error::Error>> {
+    player_name: String,
+) -> Result<ServerConnection, Box<dyn std::error::Error>> {
     let stream = TcpStream::connect(address).await?;
-    Ok(Arc::new(Mutex::new(stream)))
+    let (read_stream, write_stream) = stream.into_split();
+
+    let (outgoing_tx, outgoing_rx) = mpsc::unbounded_channel::<Message>();
+    let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<Message>();
+
+    outgoing_tx.send(Message::Connect {
+        protocol_version: PROTOCOL_VERSION,
+        client_build: env!("CARGO_PKG_VERSION").to_string(),
+        player_name,
+        wire_formats: preferred_wire_formats(),
+    })?;
+
+    let codec = MessageCodec::new(DEFAULT_MAX_FRAME_SIZE);
+    let (format_tx, format_rx) = watch::channel(None);
+    tokio::spawn(write_loop(
+        FramedWrite::new(write_stream, codec),
+        outgoing_rx,
+        format_rx,
+    ));
+    tokio::spawn(read_loop(
+        FramedRead::new(read_stream, codec),
+        incoming_tx,
+        format_tx,
+    ));
+
+    Ok(ServerConnection {
+        outgoing: outgoing_tx,
+        incoming: incoming_rx,
+    })
 }
 
 /// Version synchrone pour le menu
 pub fn connect_to_server_blocking(
     address: &str,
-) -> Result<Arc<Mutex<TcpStream>>, Box<dyn std::error::Error>> {
-    TOKIO_RUNTIME.block_on(connect_to_server(address))
+    player_name: String,
+) -> Result<ServerConnection, Box<dyn std::error::Error>> {
+    TOKIO_RUNTIME.block_on(connect_to_server(address, player_name))
 }
 
-/// Système pour envoyer un message
-pub async fn send_message(
-    stream: &Arc<Mutex<TcpStream>>,
-    message: Message,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let bytes = shared::protocol::serialization::serialize(&message)?;
-    let len = bytes.len() as u32;
-
-    let mut stream_guard = stream.lock().await;
-    stream_guard.write_u32_le(len).await?;
-    stream_guard.write_all(&bytes).await?;
-    Ok(())
-}
-
-/// Système pour recevoir un message
-pub async fn receive_message(
-    stream: &Arc<Mutex<TcpStream>>,
-) -> Result<Option<Message>, Box<dyn std::error::Error>> {
-    let mut stream_guard = stream.lock().await;
-    let len = match stream_guard.read_u32_le().await {
-        Ok(len) => len,
-        Err(_) => return Ok(None),
+/// Tâche d'écriture : envoie au serveur les messages mis en file
+///
+/// Le premier message (le `Connect`) part en bincode ; les suivants attendent
+/// que la tâche de lecture ait reçu le `Welcome` et son format.
+async fn write_loop(
+    mut writer: FramedWrite<OwnedWriteHalf, MessageCodec>,
+    mut outgoing: mpsc::UnboundedReceiver<Message>,
+    mut format: watch::Receiver<Option<WireFormat>>,
+) {
+    let Some(connect) = outgoing.recv().await else {
+        return;
+    };
+    if let Err(e) = writer.send(connect).await {
+        eprintln!("Erreur envoi message: {}", e);
+        return;
+    }
+
+    // Connexion refusée ou fermée avant le `Welcome` : plus rien à envoyer
+    let Ok(Some(wire_format)) = format.wait_for(Option::is_some).await.map(|f| *f) else {
+        return;
     };
+    writer.encoder_mut().set_format(wire_format);
 
-    let mut buffer = vec![0u8; len as usize];
-    stream_guard.read_exact(&mut buffer).await?;
+    while let Some(message) = outgoing.recv().await {
+        if let Err(e) = writer.send(message).await {
+            eprintln!("Erreur envoi message: {}", e);
+            break;
+        }
+    }
+}
 
-    let message = shared::protocol::serialization::deserialize(&buffer)?;
-    Ok(Some(message))
+/// Tâche de lecture : transmet chaque message décodé au canal entrant
+///
+/// À la réception du `Welcome`, bascule le décodage sur le format négocié et
+/// le transmet à la tâche d'écriture.
+async fn read_loop(
+    mut reader: FramedRead<OwnedReadHalf, MessageCodec>,
+    incoming: mpsc::UnboundedSender<Message>,
+    format: watch::Sender<Option<WireFormat>>,
+) {
+    while let Some(result) = reader.next().await {
+        match result {
+            Ok(message) => {
+                if let Message::Welcome { wire_format, .. } = &message {
+                    reader.decoder_mut().set_format(*wire_format);
+                    let _ = format.send(Some(*wire_format));
+                }
+                if incoming.send(message).is_err() {
+                    // Le client a abandonné la connexion
+                    return;
+                }
+            }
+            Err(e) => {
+                eprintln!("Erreur réception: {}", e);
+                return;
+            }
+        }
+    }
+    println!("Connexion fermée par le serveur");
 }
 
 /// Système Bevy pour gérer les événements réseau
+///
+/// Chaque message part dans une `Request` numérotée. Les déplacements sont
+/// appliqués immédiatement à l'état local et annulés si le serveur les refuse
+/// ou ne répond pas à temps.
 pub fn handle_network_events(
     mut network_events: EventReader<NetworkEvent>,
-    network_connection: Option<Res<NetworkConnection>>,
-    _game_state: Res<crate::game::GameState>,
+    mut network_connection: ResMut<NetworkConnection>,
+    mut game_state: ResMut<crate::game::GameState>,
 ) {
-    if let Some(conn) = network_connection {
-        if !conn.connected {
-            return;
-        }
+    if !network_connection.connected {
+        return;
+    }
 
-        if let Some(stream) = &conn.stream {
-            for event in network_events.read() {
-                let stream_clone = stream.clone();
-                match event {
-                    NetworkEvent::SendMove(player_id, position) => {
-                        let message = Message::Move {
-                            player_id: *player_id,
-                            target_position: *position,
-                        };
-                        TOKIO_RUNTIME.spawn(async move {
-                            if let Err(e) = send_message(&stream_clone, message).await {
-                                eprintln!("Erreur envoi message: {}", e);
-                            }
-                        });
-                    }
-                    NetworkEvent::SendAttack(attacker_id, target_id) => {
-                        let message = Message::Attack {
-                            attacker_id: *attacker_id,
-                            target_id: *target_id,
-                        };
-                        TOKIO_RUNTIME.spawn(async move {
-                            if let Err(e) = send_message(&stream_clone, message).await {
-                                eprintln!("Erreur envoi message: {}", e);
-                            }
-                        });
-                    }
-                    NetworkEvent::EndTurn(player_id) => {
-                        let message = Message::EndTurn {
-                            player_id: *player_id,
-                        };
-                        TOKIO_RUNTIME.spawn(async move {
-                            if let Err(e) = send_message(&stream_clone, message).await {
-                                eprintln!("Erreur envoi message: {}", e);
-                            }
-                        });
-                    }
-                    _ => {}
+    let network = &mut *network_connection;
+    let Some(connection) = &network.connection else {
+        return;
+    };
+
+    for event in network_events.read() {
+        let mut rollback = None;
+        let message = match event {
+            NetworkEvent::SendMove(player_id, position) => {
+                let path = find_local_path(&game_state, *player_id, *position);
+                if let Some(path) = &path {
+                    rollback = apply_optimistic_move(&mut game_state, *player_id, path);
+                }
+                Message::Move {
+                    player_id: *player_id,
+                    target_position: *position,
+                    path,
                 }
             }
+            NetworkEvent::SendAttack(attacker_id, target_id) => Message::Attack {
+                attacker_id: *attacker_id,
+                target_id: *target_id,
+            },
+            NetworkEvent::CastSpell(caster_id, spell_id, target_cell) => Message::CastSpell {
+                caster_id: *caster_id,
+                spell_id: *spell_id,
+                target_cell: *target_cell,
+            },
+            NetworkEvent::EndTurn(player_id) => Message::EndTurn {
+                player_id: *player_id,
+            },
+            NetworkEvent::Place(player_id, cell) => Message::Place {
+                player_id: *player_id,
+                cell: *cell,
+            },
+            NetworkEvent::Ready(player_id, ready) => Message::Ready {
+                player_id: *player_id,
+                ready: *ready,
+            },
+        };
+
+        let request_id = network.pending.insert(message.clone(), rollback);
+        if let Err(e) = connection.send(message.into_request(request_id)) {
+            eprintln!("Erreur envoi message: {}", e);
+            if let Some(request) = network.pending.complete(request_id) {
+                rollback_request(&mut game_state, request);
+            }
         }
     }
 }
 
+/// Cherche localement le plus court chemin libre du joueur vers une case
+///
+/// Le chemin est envoyé au serveur, qui le valide pas à pas : le joueur suit
+/// donc exactement le chemin animé par le client.
+fn find_local_path(
+    game_state: &crate::game::GameState,
+    player_id: PlayerId,
+    target: Position,
+) -> Option<Vec<Position>> {
+    let map = game_state.map.as_ref()?;
+    let world_state = game_state.world_state.as_ref()?;
+    let from = world_state.get_player(player_id)?.position;
+    pathfinding::find_path(map, from, target, |cell| {
+        world_state
+            .players
+            .iter()
+            .any(|p| p.position == cell && p.is_alive && p.id != player_id)
+    })
+}
+
+/// Déplace le joueur localement sans attendre le serveur
+///
+/// Retourne la position et les PM du joueur avant le déplacement, à restaurer
+/// en cas de refus.
+fn apply_optimistic_move(
+    game_state: &mut crate::game::GameState,
+    player_id: PlayerId,
+    path: &[Position],
+) -> Option<MoveRollback> {
+    let player = game_state.world_state.as_mut()?.get_player_mut(player_id)?;
+    let previous = MoveRollback {
+        player_id,
+        position: player.position,
+        movement_points: player.movement_points,
+    };
+
+    player.position = *path.last()?;
+    player.movement_points = player
+        .movement_points
+        .saturating_sub(pathfinding::path_cost(path));
+    game_state.pending_moves.push((player_id, path.to_vec()));
+
+    Some(previous)
+}
+
+/// Restaure la position et les PM du joueur déplacé de manière optimiste par une requête
+fn rollback_request(game_state: &mut crate::game::GameState, request: PendingRequest) {
+    let Some(previous) = request.rollback else {
+        return;
+    };
+    if let Some(player) = game_state
+        .world_state
+        .as_mut()
+        .and_then(|world_state| world_state.get_player_mut(previous.player_id))
+    {
+        player.position = previous.position;
+        player.movement_points = previous.movement_points;
+    }
+}
+
 /// Système pour recevoir les messages du serveur
-/// Note: Ce système est appelé à chaque frame mais ne devrait lancer qu'une seule tâche
+///
+/// Vide à chaque frame le canal alimenté par la tâche de lecture et applique
+/// les messages reçus à `GameState`. Les requêtes restées sans réponse au-delà
+/// de `REQUEST_TIMEOUT` sont abandonnées et leur effet optimiste annulé. Un
+/// refus ou une perte de connexion ramène au menu principal.
 pub fn receive_from_server(
-    network_connection: Option<Res<NetworkConnection>>,
+    mut network_connection: ResMut<NetworkConnection>,
     mut game_state: ResMut<crate::game::GameState>,
+    mut connection_settings: ResMut<crate::ui::ConnectionSettings>,
+    mut next_state: ResMut<NextState<crate::AppState>>,
 ) {
-    // Pour éviter de lancer plusieurs tâches, on vérifie si une connexion existe
-    // mais on ne fait rien ici car la réception devrait être gérée différemment
-    // Pour l'instant, on va simplifier en ne recevant pas de messages
-    // Une meilleure implémentation utiliserait des channels ou un système d'événements
-    
-    if let Some(conn) = network_connection {
-        if !conn.connected {
-            return;
+    let network = &mut *network_connection;
+    let Some(connection) = network.connection.as_mut() else {
+        return;
+    };
+
+    let mut close_reason = None;
+    loop {
+        match connection.incoming.try_recv() {
+            Ok(Message::ConnectionRejected { reason }) => {
+                close_reason = Some(format!("Erreur: connexion refusée ({})", reason));
+                break;
+            }
+            Ok(message) => {
+                apply_server_message(message, &mut game_state, &mut network.pending, connection)
+            }
+            Err(mpsc::error::TryRecvError::Empty) => break,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                close_reason = Some("Erreur: déconnecté du serveur".to_string());
+                break;
+            }
         }
+    }
 
-        if let Some(stream) = &conn.stream {
-            let stream_clone = stream.clone();
-            
-            // Essaie de recevoir un message de manière non-bloquante
-            // Pour une implémentation simple, on pourrait utiliser un polling
-            TOKIO_RUNTIME.spawn(async move {
-                match receive_message(&stream_clone).await {
-                    Ok(Some(message)) => {
-                        match message {
-                            Message::Welcome {
-                                player_id,
-                                world_state,
-                            } => {
-                                println!("✓ Bienvenue joueur {} !", player_id);
-                                // TODO: Mettre à jour game_state via un canal
-                            }
-                            Message::Sync { world_state } => {
-                                println!("✓ Synchronisation reçue");
-                                // TODO: Mettre à jour game_state via un canal
-                            }
-                            Message::Response { success, message } => {
-                                if success {
-                                    println!("✓ {}", message);
-                                } else {
-                                    println!("✗ {}", message);
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                    Ok(None) => {
-                        println!("Connexion fermée par le serveur");
-                    }
-                    Err(e) => {
-                        eprintln!("Erreur réception: {}", e);
+    for request in network.pending.expire(Instant::now(), REQUEST_TIMEOUT) {
+        println!("✗ {:?} sans réponse du serveur", request.message);
+        rollback_request(&mut game_state, request);
+    }
+
+    if let Some(reason) = close_reason {
+        println!("{}", reason);
+        network_connection.connection = None;
+        network_connection.connected = false;
+        network_connection.pending = PendingRequests::default();
+        *game_state = crate::game::GameState::default();
+        connection_settings.status_message = reason;
+        next_state.set(crate::AppState::MainMenu);
+    }
+}
+
+/// Applique un message du serveur à l'état local du jeu
+fn apply_server_message(
+    message: Message,
+    game_state: &mut crate::game::GameState,
+    pending: &mut PendingRequests,
+    connection: &ServerConnection,
+) {
+    match message {
+        Message::Welcome {
+            player_id,
+            wire_format,
+            sequence,
+            world_state,
+            map,
+        } => {
+            println!(
+                "✓ Bienvenue joueur {} ! (format {})",
+                player_id, wire_format
+            );
+            game_state.my_player_id = Some(player_id);
+            game_state.map = Some(map);
+            game_state.set_world_state(sequence, world_state);
+        }
+        Message::Sync {
+            sequence,
+            world_state,
+        } => {
+            game_state.set_world_state(sequence, world_state);
+        }
+        Message::Delta { delta } => apply_delta_or_resync(game_state, &delta, connection),
+        Message::Events { events } => {
+            game_state.record_events(events);
+        }
+        Message::FightEnded { winners, losers } => {
+            println!(
+                "Combat terminé : vainqueurs {:?}, vaincus {:?}",
+                winners, losers
+            );
+            game_state.winners = Some(winners);
+        }
+        Message::Response { request_id, result } => {
+            let request = request_id.and_then(|request_id| pending.complete(request_id));
+            match result {
+                Ok(message) => println!("✓ {}", message),
+                Err(error) => match request {
+                    Some(request) => {
+                        println!("✗ {:?} refusé : {}", request.message, error);
+                        // Annule l'effet optimiste de la requête refusée
+                        rollback_request(game_state, request);
                     }
-                }
-            });
+                    None => println!("✗ {}", error),
+                },
+            }
         }
+        _ => {}
+    }
+}
+
+/// Applique un delta, ou demande un état complet si la séquence a un trou
+fn apply_delta_or_resync(
+    game_state: &mut crate::game::GameState,
+    delta: &WorldDelta,
+    connection: &ServerConnection,
+) {
+    // Un seul Resync à la fois : les deltas suivants sont ignorés jusqu'au Sync
+    if game_state.apply_delta(delta) || game_state.resync_pending {
+        return;
+    }
+
+    println!(
+        "⚠ Delta {} inattendu (séquence locale {:?}), resynchronisation",
+        delta.base_sequence, game_state.sequence
+    );
+    game_state.resync_pending = true;
+    if let Err(e) = connection.send(Message::Resync) {
+        eprintln!("Erreur envoi message: {}", e);
     }
 }
@@ -1,13 +1,17 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
-use crate::game::GameState;
+use crate::game::{active_fighter, spell_target, GameState, SPELL_KEY_LABELS};
 use crate::network;
+use shared::protocol::{FightPhase, PlayerState};
+use shared::spells;
+use shared::summons;
 
 /// Ressource pour les paramètres de connexion
 #[derive(Resource)]
 pub struct ConnectionSettings {
     pub server_address: String,
+    pub player_name: String,
     pub status_message: String,
     pub connecting: bool,
 }
@@ -16,6 +20,7 @@ impl Default for ConnectionSettings {
     fn default() -> Self {
         Self {
             server_address: "127.0.0.1:8080".to_string(),
+            player_name: "Joueur".to_string(),
             status_message: String::new(),
             connecting: false,
         }
@@ -69,6 +74,11 @@ pub fn main_menu_system(
                         ui.text_edit_singleline(&mut connection_settings.server_address);
                     });
 
+                    ui.horizontal(|ui| {
+                        ui.label("Nom du joueur:");
+                        ui.text_edit_singleline(&mut connection_settings.player_name);
+                    });
+
                     ui.add_space(10.0);
 
                     if !connection_settings.status_message.is_empty() {
@@ -107,10 +117,11 @@ pub fn main_menu_system(
                                 "Connexion en cours...".to_string();
 
                             let address = connection_settings.server_address.clone();
-                            
-                            match network::connect_to_server_blocking(&address) {
-                                Ok(stream) => {
-                                    network_connection.stream = Some(stream);
+                            let player_name = connection_settings.player_name.clone();
+
+                            match network::connect_to_server_blocking(&address, player_name) {
+                                Ok(connection) => {
+                                    network_connection.connection = Some(connection);
                                     network_connection.connected = true;
                                     connection_settings.status_message =
                                         "Connexion réussie !".to_string();
@@ -158,7 +169,12 @@ pub fn main_menu_system(
 }
 
 /// Système pour afficher l'interface utilisateur pendant le jeu
-pub fn ui_system(mut contexts: EguiContexts, game_state: Res<GameState>) {
+pub fn ui_system(
+    mut contexts: EguiContexts,
+    mut game_state: ResMut<GameState>,
+    network_connection: Res<network::NetworkConnection>,
+) {
+    let mut hovered_slot = None;
     egui::Window::new("HUD")
         .title_bar(false)
         .resizable(false)
@@ -167,13 +183,60 @@ pub fn ui_system(mut contexts: EguiContexts, game_state: Res<GameState>) {
             ui.heading("État du jeu");
 
             if let Some(ref world_state) = game_state.world_state {
-                ui.label(format!("Tour: {}", world_state.turn_number));
-                ui.label(format!("Joueur actif: {}", world_state.current_turn));
+                match world_state.phase {
+                    FightPhase::Placement => {
+                        ui.label(
+                            egui::RichText::new("Placement").color(egui::Color32::YELLOW),
+                        );
+                    }
+                    FightPhase::Fighting => {
+                        ui.label(format!("Tour: {}", world_state.turn_number));
+                        ui.label(format!("Joueur actif: {}", world_state.current_turn));
+                        ui.label(format!(
+                            "Temps restant: {} s",
+                            world_state.turn_time_remaining
+                        ));
+                    }
+                    FightPhase::Ended => {
+                        ui.label("Combat terminé");
+                    }
+                }
+
+                ui.separator();
+                ui.label("Frise:");
+                for player_id in &world_state.timeline {
+                    let Some(player) = world_state.get_player(*player_id) else {
+                        continue;
+                    };
+                    let mut text = egui::RichText::new(format!(
+                        "{} (équipe {}, initiative {}){}",
+                        fighter_name(player),
+                        player.team,
+                        player.initiative(),
+                        if player.afk { " - absent" } else { "" }
+                    ));
+                    if world_state.phase == FightPhase::Placement {
+                        if player.ready {
+                            text = text.color(egui::Color32::GREEN);
+                        }
+                    } else if !player.is_alive {
+                        text = text.strikethrough().color(egui::Color32::GRAY);
+                    } else if player.id == world_state.current_turn {
+                        text = text.strong().color(egui::Color32::GREEN);
+                    }
+                    ui.label(text);
+                }
 
                 if let Some(my_id) = game_state.my_player_id {
-                    if let Some(player) = world_state.get_player(my_id) {
+                    // Pendant son tour, une invocation dirigée remplace le personnage
+                    let fighter_id = active_fighter(&game_state, my_id);
+                    if let Some(player) = world_state.get_player(fighter_id) {
                         ui.separator();
-                        ui.label(format!("Votre joueur (ID: {})", my_id));
+                        if fighter_id == my_id {
+                            ui.label(format!("Votre joueur (ID: {})", my_id));
+                        } else {
+                            ui.label(format!("Votre invocation: {}", fighter_name(player)));
+                        }
                         ui.label(format!(
                             "Position: ({}, {})",
                             player.position.x, player.position.y
@@ -186,7 +249,53 @@ pub fn ui_system(mut contexts: EguiContexts, game_state: Res<GameState>) {
                             if player.is_alive { "Vivant" } else { "Mort" }
                         ));
 
-                        if world_state.current_turn == my_id {
+                        ui.separator();
+                        ui.label("Sorts:");
+                        for (slot, known) in player.spells.iter().enumerate() {
+                            let Some(spell) = spells::get(known.spell_id) else {
+                                continue;
+                            };
+                            let key = SPELL_KEY_LABELS.get(slot).copied().unwrap_or("-");
+                            let mut line = format!(
+                                "{}. {} ({} PA, portée {}-{})",
+                                key,
+                                spell.name,
+                                spell.ap_cost,
+                                spell.min_range,
+                                spell.max_range
+                            );
+                            if known.cooldown_remaining > 0 {
+                                line.push_str(&format!(" - relance {}", known.cooldown_remaining));
+                            }
+                            // Grise les sorts sans cible à portée et en ligne de vue
+                            let mut text = egui::RichText::new(line);
+                            if spell_target(&game_state, fighter_id, slot).is_none() {
+                                text = text.color(egui::Color32::GRAY);
+                            }
+                            // Survoler un sort prévisualise sa zone d'effet
+                            if ui.label(text).hovered() {
+                                hovered_slot = Some(slot);
+                            }
+                        }
+
+                        if !player.effects.is_empty() {
+                            ui.separator();
+                            ui.label("Effets:");
+                            for effect in &player.effects {
+                                ui.label(format!(
+                                    "{} ({} tours, joueur {})",
+                                    effect.kind, effect.turns_remaining, effect.caster_id
+                                ));
+                            }
+                        }
+
+                        if world_state.phase == FightPhase::Placement {
+                            ui.label(if player.ready {
+                                "Vous êtes prêt"
+                            } else {
+                                "Choisissez votre cellule de départ"
+                            });
+                        } else if world_state.current_turn == fighter_id {
                             ui.label(
                                 egui::RichText::new("C'est votre tour !")
                                     .color(egui::Color32::GREEN),
@@ -200,22 +309,83 @@ pub fn ui_system(mut contexts: EguiContexts, game_state: Res<GameState>) {
                 for player in &world_state.players {
                     if Some(player.id) != game_state.my_player_id {
                         ui.label(format!(
-                            "Joueur {}: ({}, {}) - Vie: {}/{}",
-                            player.id,
+                            "{}: ({}, {}) - Vie: {}/{}",
+                            fighter_name(player),
                             player.position.x,
                             player.position.y,
                             player.health,
                             player.max_health
                         ));
+                        for effect in &player.effects {
+                            ui.label(format!(
+                                "  • {} ({} tours)",
+                                effect.kind, effect.turns_remaining
+                            ));
+                        }
                     }
                 }
             } else {
                 ui.label("En attente de synchronisation...");
             }
 
+            let pending = network_connection.pending.len();
+            if pending > 0 {
+                ui.label(format!("Actions en attente: {}", pending));
+            }
+
             ui.separator();
             ui.label("Contrôles:");
             ui.label("Flèches/WASD: Déplacer");
             ui.label("Espace: Terminer le tour");
+            ui.label("1-0, -, =, F1-F11: Lancer un sort");
+            ui.label("Survol d'un sort: Zone d'effet");
+            ui.label("Tab: Changer de cellule de départ");
+            ui.label("R: Prêt / pas prêt");
         });
+    if game_state.previewed_slot != hovered_slot {
+        game_state.previewed_slot = hovered_slot;
+    }
+    if let Some(winners) = &game_state.winners {
+        let victory = game_state
+            .my_player_id
+            .is_some_and(|my_id| winners.contains(&my_id));
+        egui::Window::new("Fin du combat")
+            .resizable(false)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 10.0])
+            .show(contexts.ctx_mut(), |ui| {
+                let (text, color) = if victory {
+                    ("Victoire !", egui::Color32::GREEN)
+                } else {
+                    ("Défaite...", egui::Color32::RED)
+                };
+                ui.label(egui::RichText::new(text).heading().color(color));
+            });
+    }
+    egui::Window::new("Journal de combat")
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+        .show(contexts.ctx_mut(), |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for event in &game_state.combat_log {
+                        ui.label(event.to_string());
+                    }
+                });
+        });
+}
+
+/// Nom d'un combattant : numéro du joueur, ou créature et invocateur pour une invocation
+fn fighter_name(player: &PlayerState) -> String {
+    match player.summon {
+        Some(summon) => format!(
+            "{} {} (invocation du joueur {})",
+            summons::get(summon.creature_id).map_or("Créature", |creature| creature.name),
+            player.id,
+            summon.summoner_id
+        ),
+        None => format!("Joueur {}", player.id),
+    }
 }
@@ -0,0 +1,19 @@
+-- Plan des cellules des cartes
+
+-- Une ligne de caractères par rangée : '.' praticable, 'o' trou, '#' obstacle,
+-- 'r' / 'b' cellule de départ de l'équipe rouge / bleue. Sans plan, la carte
+-- est entièrement praticable.
+ALTER TABLE maps ADD COLUMN IF NOT EXISTS layout TEXT;
+
+UPDATE maps SET layout =
+'..........
+.r......b.
+...#......
+.r...o..b.
+....##....
+.r..o.#.b.
+......#...
+.r......b.
+...o......
+..........'
+WHERE name = 'Plaine des débutants' AND layout IS NULL;
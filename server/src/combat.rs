@@ -0,0 +1,313 @@
+//! Formule de calcul des dégâts élémentaires
+//!
+//! Les dégâts d'un sort sont calculés en quatre étapes :
+//! 1. tirage entre les dégâts minimum et maximum du sort ;
+//! 2. bonus de la caractéristique du lanceur liée à l'élément (+1 % par point) ;
+//! 3. résistances de la cible à l'élément, en pourcentage puis fixes ;
+//! 4. réduction de dommages de la cible, quel que soit l'élément.
+//!
+//! Les retraits de PA/PM sont tirés point par point, chacun pouvant être
+//! esquivé selon la sagesse du lanceur et de la cible.
+//!
+//! Le tacle, lui, ne doit rien au hasard : quitter une case au contact
+//! d'ennemis coûte une part des PA et PM restants, fixée par la fuite du
+//! joueur et le tacle des ennemis.
+//!
+//! Une poussée arrêtée par un obstacle ou un combattant inflige des dégâts de
+//! collision, fixes par case de poussée restante.
+
+use crate::rng::FightRng;
+use shared::protocol::{Element, Resistances, Stats};
+
+/// Résistance maximale en pourcentage, au-delà les points sont ignorés
+pub const MAX_PERCENT_RESISTANCE: i32 = 50;
+
+/// Probabilité minimale de retirer un point de PA/PM, en pourcentage
+pub const MIN_REMOVAL_CHANCE: u32 = 10;
+
+/// Probabilité maximale de retirer un point de PA/PM, en pourcentage
+pub const MAX_REMOVAL_CHANCE: u32 = 90;
+
+/// Dégâts de collision par case de poussée restante
+pub const COLLISION_DAMAGE_PER_CELL: u32 = 8;
+
+/// Tire les dégâts de base d'un sort entre `min` et `max` inclus
+pub fn roll_damage(rng: &mut FightRng, min: u32, max: u32) -> u32 {
+    rng.range(min, max)
+}
+
+/// Applique caractéristiques et résistances à des dégâts de base
+pub fn compute_damage(
+    base_damage: u32,
+    element: Element,
+    attacker: &Stats,
+    target: &Resistances,
+) -> u32 {
+    // Une caractéristique négative réduit les dégâts, jusqu'à les annuler
+    let characteristic = attacker.for_element(element).max(-100);
+    let boosted = base_damage as i64 * (100 + characteristic) as i64 / 100;
+
+    let resistance = target.for_element(element);
+    let percent = resistance.percent.min(MAX_PERCENT_RESISTANCE);
+    let resisted = boosted * (100 - percent) as i64 / 100 - resistance.flat as i64;
+
+    let reduced = resisted - target.damage_reduction as i64;
+    reduced.clamp(0, u32::MAX as i64) as u32
+}
+
+/// Probabilité de retirer un point de PA/PM à la cible, en pourcentage
+///
+/// 50 % de base, multipliés par le rapport entre le retrait du lanceur et
+/// l'esquive de la cible (1 + sagesse / 10 chacun), puis par la part de
+/// points qu'il reste à la cible. Le résultat est borné entre 10 et 90 %.
+pub fn removal_chance(caster: &Stats, target: &Stats, remaining: u32, base: u32) -> u32 {
+    if remaining == 0 || base == 0 {
+        return 0;
+    }
+
+    let removal = (1 + caster.wisdom / 10).max(1) as i64;
+    let dodge = (1 + target.wisdom / 10).max(1) as i64;
+    let chance = 50 * removal * remaining as i64 / (dodge * base as i64);
+    chance.clamp(MIN_REMOVAL_CHANCE as i64, MAX_REMOVAL_CHANCE as i64) as u32
+}
+
+/// Tire les points retirés sur `amount` tentatives
+///
+/// Retourne le nombre de points retirés ; les autres ont été esquivés. La
+/// probabilité est recalculée après chaque point retiré.
+pub fn roll_removal(
+    rng: &mut FightRng,
+    amount: u32,
+    caster: &Stats,
+    target: &Stats,
+    remaining: u32,
+    base: u32,
+) -> u32 {
+    let mut removed = 0;
+    for _ in 0..amount {
+        if removed == remaining {
+            break;
+        }
+        if rng.chance(removal_chance(caster, target, remaining - removed, base)) {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Points perdus en quittant une case au contact d'ennemis
+///
+/// Le joueur conserve la part (fuite + 2) / (2 × Σ (tacle + 2)) de ses
+/// `points`, arrondie à l'inférieur, la somme portant sur les ennemis
+/// adjacents. Il ne perd rien si cette part atteint 1.
+pub fn tackle_loss(dodge: i32, tacklers: &[i32], points: u32) -> u32 {
+    let escape = (dodge + 2).max(0) as i64;
+    let hold = 2 * tacklers
+        .iter()
+        .map(|&tackle| (tackle + 2).max(0) as i64)
+        .sum::<i64>();
+    if tacklers.is_empty() || escape >= hold {
+        return 0;
+    }
+    let kept = points as i64 * escape / hold;
+    points - kept as u32
+}
+
+/// Dégâts subis par un joueur poussé contre un obstacle ou un combattant
+///
+/// Le combattant heurté en subit la moitié.
+pub fn collision_damage(remaining_distance: u32) -> u32 {
+    COLLISION_DAMAGE_PER_CELL * remaining_distance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::protocol::Resistance;
+
+    #[test]
+    fn test_no_stats_no_resistances() {
+        let damage = compute_damage(
+            20,
+            Element::Earth,
+            &Stats::default(),
+            &Resistances::default(),
+        );
+        assert_eq!(damage, 20);
+    }
+
+    #[test]
+    fn test_characteristic_matches_element() {
+        let stats = Stats {
+            strength: 100,
+            intelligence: 50,
+            chance: 25,
+            agility: -50,
+            ..Default::default()
+        };
+        let no_resistances = Resistances::default();
+
+        assert_eq!(
+            compute_damage(20, Element::Earth, &stats, &no_resistances),
+            40
+        );
+        assert_eq!(
+            compute_damage(20, Element::Fire, &stats, &no_resistances),
+            30
+        );
+        assert_eq!(
+            compute_damage(20, Element::Water, &stats, &no_resistances),
+            25
+        );
+        assert_eq!(
+            compute_damage(20, Element::Air, &stats, &no_resistances),
+            10
+        );
+    }
+
+    #[test]
+    fn test_very_negative_characteristic_cancels_damage() {
+        let stats = Stats {
+            strength: -300,
+            ..Default::default()
+        };
+        assert_eq!(
+            compute_damage(20, Element::Earth, &stats, &Resistances::default()),
+            0
+        );
+    }
+
+    #[test]
+    fn test_percent_then_flat_resistance() {
+        let resistances = Resistances {
+            fire: Resistance {
+                percent: 25,
+                flat: 5,
+            },
+            ..Default::default()
+        };
+
+        // 40 - 25 % = 30, puis - 5
+        assert_eq!(
+            compute_damage(40, Element::Fire, &Stats::default(), &resistances),
+            25
+        );
+        // Les autres éléments ne sont pas résistés
+        assert_eq!(
+            compute_damage(40, Element::Water, &Stats::default(), &resistances),
+            40
+        );
+    }
+
+    #[test]
+    fn test_percent_resistance_is_capped() {
+        let resistances = Resistances {
+            air: Resistance {
+                percent: 90,
+                flat: 0,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            compute_damage(40, Element::Air, &Stats::default(), &resistances),
+            20
+        );
+    }
+
+    #[test]
+    fn test_negative_resistance_is_a_weakness() {
+        let resistances = Resistances {
+            water: Resistance {
+                percent: -50,
+                flat: 0,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            compute_damage(40, Element::Water, &Stats::default(), &resistances),
+            60
+        );
+    }
+
+    #[test]
+    fn test_damage_reduction_applies_last_and_never_heals() {
+        let resistances = Resistances {
+            earth: Resistance {
+                percent: 50,
+                flat: 0,
+            },
+            damage_reduction: 8,
+            ..Default::default()
+        };
+        assert_eq!(
+            compute_damage(20, Element::Earth, &Stats::default(), &resistances),
+            2
+        );
+        assert_eq!(
+            compute_damage(10, Element::Earth, &Stats::default(), &resistances),
+            0
+        );
+    }
+
+    #[test]
+    fn test_removal_chance() {
+        let neutral = Stats::default();
+        let wise = Stats {
+            wisdom: 100,
+            ..Default::default()
+        };
+
+        // Lanceur et cible équivalents, cible à plein PA : 50 %
+        assert_eq!(removal_chance(&neutral, &neutral, 6, 6), 50);
+        // Moins il reste de points, plus ils sont faciles à esquiver
+        assert_eq!(removal_chance(&neutral, &neutral, 3, 6), 25);
+        // La sagesse de la cible augmente l'esquive, jusqu'au minimum
+        assert_eq!(removal_chance(&neutral, &wise, 6, 6), MIN_REMOVAL_CHANCE);
+        // La sagesse du lanceur augmente le retrait, jusqu'au maximum
+        assert_eq!(removal_chance(&wise, &neutral, 6, 6), MAX_REMOVAL_CHANCE);
+        assert_eq!(removal_chance(&neutral, &neutral, 0, 6), 0);
+    }
+
+    #[test]
+    fn test_roll_removal_never_exceeds_remaining() {
+        let mut rng = FightRng::new(11);
+        let wise = Stats {
+            wisdom: 1000,
+            ..Default::default()
+        };
+        for _ in 0..100 {
+            let removed = roll_removal(&mut rng, 5, &wise, &Stats::default(), 2, 6);
+            assert!(removed <= 2);
+        }
+    }
+
+    #[test]
+    fn test_tackle_loss() {
+        // Fuite et tacle égaux : la moitié des points est perdue
+        assert_eq!(tackle_loss(0, &[0], 3), 2);
+        assert_eq!(tackle_loss(0, &[0], 6), 3);
+        // Chaque ennemi adjacent retient un peu plus
+        assert_eq!(tackle_loss(0, &[0, 0], 6), 5);
+        // Une fuite au moins double du tacle permet de partir librement
+        assert_eq!(tackle_loss(6, &[2], 6), 0);
+        assert_eq!(tackle_loss(0, &[], 6), 0);
+        // Un tacle très supérieur à la fuite retient tout
+        assert_eq!(tackle_loss(-2, &[10], 6), 6);
+    }
+
+    #[test]
+    fn test_collision_damage() {
+        assert_eq!(collision_damage(0), 0);
+        assert_eq!(collision_damage(2), 2 * COLLISION_DAMAGE_PER_CELL);
+    }
+
+    #[test]
+    fn test_roll_damage_stays_in_range() {
+        let mut rng = FightRng::new(7);
+        for _ in 0..100 {
+            let damage = roll_damage(&mut rng, 18, 22);
+            assert!((18..=22).contains(&damage));
+        }
+        assert_eq!(roll_damage(&mut rng, 25, 25), 25);
+    }
+}
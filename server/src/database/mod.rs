@@ -36,11 +36,16 @@ pub async fn create_pool(config: &DatabaseConfig) -> Result<PgPool> {
 
 /// Exécute les migrations de la base de données
 pub async fn run_migrations(pool: &PgPool) -> Result<()> {
-    // Lit le fichier de migration
-    let migration_sql = include_str!("../../migrations/001_init.sql");
+    // Lit les fichiers de migration, dans l'ordre
+    let migrations = [
+        include_str!("../../migrations/001_init.sql"),
+        include_str!("../../migrations/002_map_cells.sql"),
+    ];
 
-    // Exécute la migration
-    sqlx::query(migration_sql).execute(pool).await?;
+    // Exécute les migrations
+    for migration_sql in migrations {
+        sqlx::query(migration_sql).execute(pool).await?;
+    }
 
     Ok(())
 }
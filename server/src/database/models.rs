@@ -43,9 +43,22 @@ pub struct Map {
     pub map_type: String,
     pub difficulty_level: i32,
     pub is_pvp_enabled: bool,
+    /// Plan des cellules (voir `shared::map`), absent pour une carte entièrement praticable
+    pub layout: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl TryFrom<&Map> for shared::map::GameMap {
+    type Error = shared::map::LayoutError;
+
+    fn try_from(map: &Map) -> Result<Self, Self::Error> {
+        match &map.layout {
+            Some(layout) => Self::from_layout(map.width, map.height, layout),
+            None => Ok(Self::new(map.width, map.height)),
+        }
+    }
+}
+
 /// Modèle pour un combat
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Fight {
@@ -75,6 +88,16 @@ pub struct FightParticipant {
     pub left_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+impl FightParticipant {
+    /// Numéro en base (1 ou 2) d'une équipe du combat
+    pub fn team_number(team: shared::protocol::Team) -> i32 {
+        match team {
+            shared::protocol::Team::Red => 1,
+            shared::protocol::Team::Blue => 2,
+        }
+    }
+}
+
 /// Modèle pour les statistiques d'un personnage
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CharacterStats {
@@ -89,6 +112,19 @@ pub struct CharacterStats {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl From<&CharacterStats> for shared::protocol::Stats {
+    fn from(stats: &CharacterStats) -> Self {
+        Self {
+            strength: stats.strength,
+            intelligence: stats.intelligence,
+            chance: stats.chance,
+            agility: stats.agility,
+            vitality: stats.vitality,
+            wisdom: stats.wisdom,
+        }
+    }
+}
+
 /// Données pour créer un nouveau personnage
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewCharacter {
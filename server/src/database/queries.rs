@@ -1,4 +1,6 @@
-use super::models::{Character, CharacterStats, Fight, Map, NewCharacter, NewUser, User};
+use super::models::{
+    Character, CharacterStats, Fight, FightParticipant, Map, NewCharacter, NewUser, User,
+};
 use sqlx::{PgPool, Result};
 
 /// Crée un nouvel utilisateur
@@ -189,12 +191,33 @@ pub async fn get_character_stats(
     Ok(stats)
 }
 
+/// Récupère les stats du personnage portant un nom donné
+pub async fn get_character_stats_by_name(
+    pool: &PgPool,
+    character_name: &str,
+) -> Result<Option<CharacterStats>> {
+    let stats = sqlx::query_as::<_, CharacterStats>(
+        r#"
+        SELECT s.id, s.character_id, s.strength, s.intelligence, s.agility, s.vitality,
+               s.wisdom, s.chance, s.updated_at
+        FROM character_stats s
+        JOIN characters c ON c.id = s.character_id
+        WHERE c.name = $1
+        "#,
+    )
+    .bind(character_name)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(stats)
+}
+
 /// Récupère une carte par son ID
-#[allow(dead_code)]
 pub async fn get_map_by_id(pool: &PgPool, map_id: i32) -> Result<Option<Map>> {
     let map = sqlx::query_as::<_, Map>(
         r#"
-        SELECT id, name, width, height, map_type, difficulty_level, is_pvp_enabled, created_at
+        SELECT id, name, width, height, map_type, difficulty_level, is_pvp_enabled, layout,
+               created_at
         FROM maps
         WHERE id = $1
         "#,
@@ -211,7 +234,8 @@ pub async fn get_map_by_id(pool: &PgPool, map_id: i32) -> Result<Option<Map>> {
 pub async fn get_all_maps(pool: &PgPool) -> Result<Vec<Map>> {
     let maps = sqlx::query_as::<_, Map>(
         r#"
-        SELECT id, name, width, height, map_type, difficulty_level, is_pvp_enabled, created_at
+        SELECT id, name, width, height, map_type, difficulty_level, is_pvp_enabled, layout,
+               created_at
         FROM maps
         ORDER BY difficulty_level, name
         "#,
@@ -223,7 +247,6 @@ pub async fn get_all_maps(pool: &PgPool) -> Result<Vec<Map>> {
 }
 
 /// Crée un nouveau combat
-#[allow(dead_code)]
 pub async fn create_fight(pool: &PgPool, map_id: Option<i32>, fight_type: &str) -> Result<Fight> {
     let fight = sqlx::query_as::<_, Fight>(
         r#"
@@ -241,7 +264,6 @@ pub async fn create_fight(pool: &PgPool, map_id: Option<i32>, fight_type: &str)
 }
 
 /// Met à jour l'état d'un combat
-#[allow(dead_code)]
 pub async fn update_fight_status(
     pool: &PgPool,
     fight_id: i32,
@@ -264,6 +286,34 @@ pub async fn update_fight_status(
     Ok(())
 }
 
+/// Inscrit un personnage dans un combat, dans l'équipe donnée
+///
+/// Un personnage qui revient dans le même combat y reprend sa place.
+pub async fn add_fight_participant(
+    pool: &PgPool,
+    fight_id: i32,
+    character_id: i32,
+    team: i32,
+) -> Result<FightParticipant> {
+    let participant = sqlx::query_as::<_, FightParticipant>(
+        r#"
+        INSERT INTO fight_participants (fight_id, character_id, team)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (fight_id, character_id)
+        DO UPDATE SET team = EXCLUDED.team, left_at = NULL
+        RETURNING id, fight_id, character_id, team, is_alive, damage_dealt, damage_taken,
+                  turns_played, joined_at, left_at
+        "#,
+    )
+    .bind(fight_id)
+    .bind(character_id)
+    .bind(team)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(participant)
+}
+
 /// Réinitialise les PA/PM d'un personnage
 #[allow(dead_code)]
 pub async fn reset_character_turn_points(pool: &PgPool, character_id: i32) -> Result<()> {
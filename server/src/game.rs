@@ -1,209 +1,1587 @@
-use shared::protocol::{PlayerId, PlayerState, Position, WorldState};
+use crate::combat;
+use crate::rng::FightRng;
+use crate::timeline;
+use serde::{Deserialize, Serialize};
+use shared::area;
+use shared::effects::{ActiveEffect, StatusKind};
+use shared::line_of_sight;
+use shared::map::GameMap;
+use shared::pathfinding;
+use shared::protocol::{
+    Element, FightPhase, GameError, GameEvent, PlayerId, PlayerState, Position, Stats, TackleLoss,
+    Team, WorldState, BASE_ACTION_POINTS, BASE_MOVEMENT_POINTS,
+};
+use shared::spells::{self, RangeError, SpellEffect, SpellId};
+use shared::summons::{self, Control, CreatureId};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Durée maximale du placement, une fois les deux équipes présentes
+pub const PLACEMENT_DURATION: Duration = Duration::from_secs(30);
+
+/// Durée d'un tour par défaut, au-delà de laquelle il se termine automatiquement
+pub const DEFAULT_TURN_DURATION: Duration = Duration::from_secs(30);
+
+/// Nombre de tours consécutifs laissés au temps avant qu'un joueur soit signalé absent
+pub const AFK_TIMEOUTS: u32 = 3;
+
+/// Action acceptée par le jeu, enregistrée dans le journal du combat
+///
+/// Rejouer les actions dans l'ordre sur un jeu créé avec la même graine
+/// reproduit exactement le combat, tirages aléatoires compris.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum FightAction {
+    Join {
+        position: Position,
+        stats: Stats,
+        team: Team,
+    },
+    Leave {
+        player_id: PlayerId,
+    },
+    Move {
+        player_id: PlayerId,
+        path: Vec<Position>,
+    },
+    Attack {
+        attacker_id: PlayerId,
+        target_id: PlayerId,
+    },
+    CastSpell {
+        caster_id: PlayerId,
+        spell_id: SpellId,
+        target_cell: Position,
+    },
+    EndTurn {
+        player_id: PlayerId,
+    },
+    Place {
+        player_id: PlayerId,
+        cell: Position,
+    },
+    Ready {
+        player_id: PlayerId,
+        ready: bool,
+    },
+    /// Fin du placement à l'expiration du délai
+    StartFight,
+    /// Fin automatique d'un tour à l'expiration du délai
+    TurnTimedOut {
+        player_id: PlayerId,
+    },
+}
+
+/// Résultat d'un combat terminé
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FightOutcome {
+    pub winning_team: Team,
+    /// Combattants de l'équipe gagnante, morts et partis compris
+    pub winners: Vec<PlayerId>,
+    /// Combattants de l'équipe perdante, partis compris
+    pub losers: Vec<PlayerId>,
+}
 
 /// Gestion de la logique du jeu côté serveur
 pub struct Game {
     world_state: WorldState,
+    map: GameMap,
     player_counter: PlayerId,
+    /// Événements produits depuis la dernière diffusion
+    events: Vec<GameEvent>,
+    /// Tirages aléatoires du combat (dégâts, coups critiques, esquives)
+    rng: FightRng,
+    /// Journal des actions acceptées depuis la création du jeu
+    actions: Vec<FightAction>,
+    /// Équipe de chaque combattant qui a pris part au combat, départs compris
+    participants: Vec<(PlayerId, Team)>,
+    /// Fin du placement, fixée quand les deux équipes sont présentes
+    placement_deadline: Option<Instant>,
+    /// Temps accordé à chaque tour
+    turn_duration: Duration,
+    /// Fin du tour en cours, fixée au premier `tick` du tour
+    turn_deadline: Option<Instant>,
+    /// Tours consécutifs terminés par le délai, par joueur
+    consecutive_timeouts: HashMap<PlayerId, u32>,
+    /// Résultat du combat, une fois une équipe éliminée
+    outcome: Option<FightOutcome>,
+    /// Indique si la fin du combat a déjà été annoncée
+    outcome_announced: bool,
+    /// Identifiant du combat en base de données, s'il y est enregistré
+    fight_id: Option<i32>,
+    /// Personnage en base de chaque joueur, pour enregistrer le vainqueur
+    character_ids: HashMap<PlayerId, i32>,
 }
 
 impl Game {
+    /// Crée un jeu sur une carte générée entièrement praticable, avec une graine aléatoire
+    #[allow(dead_code)]
     pub fn new(map_width: i32, map_height: i32) -> Self {
+        Self::from_map(GameMap::new(map_width, map_height))
+    }
+
+    /// Crée un jeu sur une carte générée dont les tirages aléatoires suivent une graine donnée
+    #[allow(dead_code)]
+    pub fn with_seed(map_width: i32, map_height: i32, seed: u64) -> Self {
+        Self::from_map_with_seed(GameMap::new(map_width, map_height), seed)
+    }
+
+    /// Crée un jeu sur une carte donnée, avec une graine aléatoire
+    pub fn from_map(map: GameMap) -> Self {
+        Self::from_map_with_seed(map, fastrand::u64(..))
+    }
+
+    /// Crée un jeu sur une carte donnée dont les tirages aléatoires suivent une graine donnée
+    pub fn from_map_with_seed(map: GameMap, seed: u64) -> Self {
         Self {
-            world_state: WorldState::new(map_width, map_height),
+            world_state: WorldState::new(map.width, map.height),
+            map,
             player_counter: 1,
+            events: Vec::new(),
+            rng: FightRng::new(seed),
+            actions: Vec::new(),
+            participants: Vec::new(),
+            placement_deadline: None,
+            turn_duration: DEFAULT_TURN_DURATION,
+            turn_deadline: None,
+            consecutive_timeouts: HashMap::new(),
+            outcome: None,
+            outcome_announced: false,
+            fight_id: None,
+            character_ids: HashMap::new(),
+        }
+    }
+
+    /// Rejoue un combat depuis sa graine et son journal d'actions
+    #[allow(dead_code)]
+    pub fn replay(map: GameMap, seed: u64, actions: &[FightAction]) -> Result<Self, GameError> {
+        let mut game = Self::from_map_with_seed(map, seed);
+        for action in actions {
+            game.apply_action(action)?;
+        }
+        Ok(game)
+    }
+
+    /// Applique une action du journal
+    #[allow(dead_code)]
+    pub fn apply_action(&mut self, action: &FightAction) -> Result<(), GameError> {
+        match *action {
+            FightAction::Join {
+                position,
+                stats,
+                team,
+            } => {
+                self.add_player_to_team(position, stats, team);
+            }
+            FightAction::Leave { player_id } => {
+                self.remove_player(player_id);
+            }
+            FightAction::Move {
+                player_id,
+                ref path,
+            } => self.move_along(player_id, path.clone())?,
+            FightAction::Attack {
+                attacker_id,
+                target_id,
+            } => {
+                self.attack(attacker_id, target_id)?;
+            }
+            FightAction::CastSpell {
+                caster_id,
+                spell_id,
+                target_cell,
+            } => self.cast_spell(caster_id, spell_id, target_cell)?,
+            FightAction::EndTurn { player_id } => self.end_turn(player_id)?,
+            FightAction::Place { player_id, cell } => self.place_player(player_id, cell)?,
+            FightAction::Ready { player_id, ready } => self.set_ready(player_id, ready)?,
+            FightAction::StartFight => self.start_fight()?,
+            FightAction::TurnTimedOut { player_id } => self.time_out_turn(player_id)?,
+        }
+        Ok(())
+    }
+
+    /// Graine des tirages aléatoires du combat
+    #[allow(dead_code)]
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Journal des actions acceptées
+    #[allow(dead_code)]
+    pub fn actions(&self) -> &[FightAction] {
+        &self.actions
+    }
+
+    /// Change le temps accordé à chaque tour, à partir du prochain tour
+    pub fn set_turn_duration(&mut self, turn_duration: Duration) {
+        self.turn_duration = turn_duration;
+    }
+
+    /// Carte du combat
+    pub fn map(&self) -> &GameMap {
+        &self.map
+    }
+
+    /// Identifiant du combat en base de données
+    pub fn fight_id(&self) -> Option<i32> {
+        self.fight_id
+    }
+
+    /// Associe le combat à son enregistrement en base de données
+    pub fn set_fight_id(&mut self, fight_id: i32) {
+        self.fight_id = Some(fight_id);
+    }
+
+    /// Personnage en base d'un joueur
+    pub fn character_id(&self, player_id: PlayerId) -> Option<i32> {
+        self.character_ids.get(&player_id).copied()
+    }
+
+    /// Associe un joueur au personnage qu'il incarne en base de données
+    pub fn set_character_id(&mut self, player_id: PlayerId, character_id: i32) {
+        self.character_ids.insert(player_id, character_id);
+    }
+
+    /// Résultat du combat, s'il est terminé
+    pub fn outcome(&self) -> Option<&FightOutcome> {
+        self.outcome.as_ref()
+    }
+
+    /// Retourne le résultat du combat la première fois qu'il est disponible
+    ///
+    /// Permet d'annoncer la fin du combat une seule fois.
+    pub fn take_fight_end(&mut self) -> Option<FightOutcome> {
+        if self.outcome_announced {
+            return None;
+        }
+        let outcome = self.outcome.clone()?;
+        self.outcome_announced = true;
+        Some(outcome)
+    }
+
+    /// Refuse les actions de combat pendant le placement et une fois le combat terminé
+    fn check_fight_running(&self) -> Result<(), GameError> {
+        match self.world_state.phase {
+            FightPhase::Placement => Err(GameError::FightNotStarted),
+            FightPhase::Fighting => Ok(()),
+            FightPhase::Ended => Err(GameError::FightOver),
+        }
+    }
+
+    /// Refuse les actions de placement une fois le combat commencé
+    fn check_placement(&self) -> Result<(), GameError> {
+        match self.world_state.phase {
+            FightPhase::Placement => Ok(()),
+            _ => Err(GameError::NotInPlacement),
+        }
+    }
+
+    /// Termine le combat si une équipe n'a plus de combattant vivant
+    ///
+    /// Le combat ne se termine qu'une fois que les deux équipes ont eu au
+    /// moins un combattant. Vainqueurs et vaincus comptent tous ceux qui ont
+    /// pris part au combat, y compris les joueurs partis en cours de route.
+    fn check_fight_end(&mut self) {
+        if self.world_state.phase != FightPhase::Fighting {
+            return;
+        }
+        let took_part = |team: Team| self.participants.iter().any(|&(_, t)| t == team);
+        if !took_part(Team::Red) || !took_part(Team::Blue) {
+            return;
+        }
+
+        let alive = |team: Team| {
+            self.world_state
+                .players
+                .iter()
+                .any(|p| p.team == team && p.is_alive)
+        };
+        let winning_team = match (alive(Team::Red), alive(Team::Blue)) {
+            (true, false) => Team::Red,
+            (false, true) => Team::Blue,
+            _ => return,
+        };
+        let members = |team: Team| {
+            self.participants
+                .iter()
+                .filter(|&&(_, t)| t == team)
+                .map(|&(id, _)| id)
+                .collect()
+        };
+        self.outcome = Some(FightOutcome {
+            winning_team,
+            winners: members(winning_team),
+            losers: members(winning_team.opponent()),
+        });
+        self.world_state.phase = FightPhase::Ended;
+    }
+
+    /// Indique si chaque équipe compte au moins un joueur
+    fn both_teams_present(&self) -> bool {
+        [Team::Red, Team::Blue]
+            .into_iter()
+            .all(|team| self.world_state.players.iter().any(|p| p.team == team))
+    }
+
+    /// Place un joueur sur une cellule de départ de son équipe
+    ///
+    /// Si un allié occupe déjà la cellule, il prend l'ancienne place du joueur.
+    pub fn place_player(&mut self, player_id: PlayerId, cell: Position) -> Result<(), GameError> {
+        self.check_placement()?;
+        let player = self
+            .world_state
+            .get_player(player_id)
+            .ok_or(GameError::PlayerNotFound { player_id })?;
+        let (team, previous) = (player.team, player.position);
+
+        if !self.map.starting_cells(team).contains(&cell) {
+            return Err(GameError::InvalidStartingCell { position: cell });
+        }
+        let occupant = self
+            .world_state
+            .players
+            .iter()
+            .find(|p| p.position == cell && p.id != player_id)
+            .map(|p| (p.id, p.team));
+        if let Some((_, occupant_team)) = occupant {
+            if occupant_team != team {
+                return Err(GameError::CellOccupied { position: cell });
+            }
+        }
+
+        if let Some(player) = self.world_state.get_player_mut(player_id) {
+            player.position = cell;
+        }
+        self.events.push(GameEvent::Placed {
+            player_id,
+            position: cell,
+        });
+        if let Some((occupant_id, _)) = occupant {
+            if let Some(ally) = self.world_state.get_player_mut(occupant_id) {
+                ally.position = previous;
+            }
+            self.events.push(GameEvent::Placed {
+                player_id: occupant_id,
+                position: previous,
+            });
+        }
+        self.actions.push(FightAction::Place { player_id, cell });
+        Ok(())
+    }
+
+    /// Valide ou annule le placement d'un joueur
+    ///
+    /// Le combat commence dès que tous les joueurs des deux équipes sont prêts.
+    pub fn set_ready(&mut self, player_id: PlayerId, ready: bool) -> Result<(), GameError> {
+        self.check_placement()?;
+        let player = self
+            .world_state
+            .get_player_mut(player_id)
+            .ok_or(GameError::PlayerNotFound { player_id })?;
+        player.ready = ready;
+
+        self.events
+            .push(GameEvent::ReadyChanged { player_id, ready });
+        self.actions.push(FightAction::Ready { player_id, ready });
+        self.start_fight_if_ready();
+        Ok(())
+    }
+
+    /// Commence le combat si tous les joueurs des deux équipes sont prêts
+    fn start_fight_if_ready(&mut self) {
+        if self.world_state.phase == FightPhase::Placement
+            && self.both_teams_present()
+            && self.world_state.players.iter().all(|p| p.ready)
+        {
+            self.begin_fight();
+        }
+    }
+
+    /// Termine le placement et commence le combat, que les joueurs soient prêts ou non
+    pub fn start_fight(&mut self) -> Result<(), GameError> {
+        self.check_placement()?;
+        self.actions.push(FightAction::StartFight);
+        self.begin_fight();
+        Ok(())
+    }
+
+    /// Fige la frise et donne la main au premier combattant
+    fn begin_fight(&mut self) {
+        self.world_state.phase = FightPhase::Fighting;
+        self.placement_deadline = None;
+        self.participants = self
+            .world_state
+            .players
+            .iter()
+            .map(|p| (p.id, p.team))
+            .collect();
+        self.world_state.timeline = timeline::build(&self.world_state.players);
+        self.events.push(GameEvent::FightStarted {
+            timeline: self.world_state.timeline.clone(),
+        });
+        self.advance_turn(0);
+    }
+
+    /// Fait avancer les délais du combat
+    ///
+    /// Le délai de placement court dès que les deux équipes sont présentes ;
+    /// à son expiration, le combat commence même si des joueurs ne sont pas prêts.
+    /// Pendant le combat, le délai du tour part du premier appel du tour et le
+    /// temps restant est reporté dans l'état du monde, à la seconde près. Une
+    /// invocation dirigée par le serveur joue dès le premier appel de son tour.
+    pub fn tick(&mut self, now: Instant) {
+        match self.world_state.phase {
+            FightPhase::Placement => {
+                if !self.both_teams_present() {
+                    self.placement_deadline = None;
+                    return;
+                }
+                let deadline = *self
+                    .placement_deadline
+                    .get_or_insert(now + PLACEMENT_DURATION);
+                if now >= deadline {
+                    let _ = self.start_fight();
+                }
+            }
+            FightPhase::Fighting => {
+                let current_turn = self.world_state.current_turn;
+                if self.is_server_controlled(current_turn) {
+                    self.play_summon_turn(current_turn);
+                }
+                let deadline = *self.turn_deadline.get_or_insert(now + self.turn_duration);
+                let remaining = deadline.saturating_duration_since(now);
+                self.world_state.turn_time_remaining = remaining.as_secs_f64().ceil() as u32;
+                if remaining.is_zero() {
+                    let _ = self.time_out_turn(self.world_state.current_turn);
+                }
+            }
+            FightPhase::Ended => {}
+        }
+    }
+
+    /// Termine le tour d'un joueur dont le temps est écoulé
+    ///
+    /// Après `AFK_TIMEOUTS` tours consécutifs terminés ainsi, le joueur est
+    /// signalé absent.
+    fn time_out_turn(&mut self, player_id: PlayerId) -> Result<(), GameError> {
+        self.check_turn(player_id)?;
+
+        self.events.push(GameEvent::TurnTimedOut { player_id });
+        let timeouts = self.consecutive_timeouts.entry(player_id).or_insert(0);
+        *timeouts += 1;
+        if *timeouts >= AFK_TIMEOUTS {
+            self.set_afk(player_id, true);
+        }
+        self.actions.push(FightAction::TurnTimedOut { player_id });
+        self.pass_turn(player_id);
+        Ok(())
+    }
+
+    /// Signale un joueur absent ou de retour, s'il ne l'était pas déjà
+    fn set_afk(&mut self, player_id: PlayerId, afk: bool) {
+        let Some(player) = self.world_state.get_player_mut(player_id) else {
+            return;
+        };
+        if player.afk != afk {
+            player.afk = afk;
+            self.events.push(GameEvent::AfkChanged { player_id, afk });
+        }
+    }
+
+    /// Récupère les événements produits depuis le dernier appel
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Émet l'événement des PA/PM courants d'un joueur
+    fn push_ap_mp_changed(&mut self, player_id: PlayerId) {
+        if let Some(player) = self.world_state.get_player(player_id) {
+            self.events.push(GameEvent::ApMpChanged {
+                player_id,
+                action_points: player.action_points,
+                movement_points: player.movement_points,
+            });
         }
     }
 
-    /// Ajoute un nouveau joueur au jeu
+    /// Ajoute un nouveau joueur au jeu, sans caractéristiques
+    #[allow(dead_code)]
     pub fn add_player(&mut self, position: Position) -> PlayerId {
+        self.add_player_with_stats(position, Stats::default())
+    }
+
+    /// Équipe qui compte le moins de combattants, rouge à égalité
+    fn smallest_team(&self) -> Team {
+        let red = self
+            .world_state
+            .players
+            .iter()
+            .filter(|p| p.team == Team::Red)
+            .count();
+        if red * 2 > self.world_state.players.len() {
+            Team::Blue
+        } else {
+            Team::Red
+        }
+    }
+
+    /// Première cellule de départ d'une équipe qui n'est occupée par personne
+    fn free_starting_cell(&self, team: Team) -> Option<Position> {
+        self.map
+            .starting_cells(team)
+            .iter()
+            .copied()
+            .find(|cell| !self.is_position_occupied(cell, None))
+    }
+
+    /// Fait rejoindre le combat à un joueur, sur une cellule de départ libre
+    ///
+    /// Le joueur rejoint l'équipe qui compte le moins de combattants, ou l'autre
+    /// si toutes ses cellules sont prises. Retourne `None` si le combat est complet.
+    pub fn join(&mut self, stats: Stats) -> Option<PlayerId> {
+        let preferred = self.smallest_team();
+        let (team, cell) = [preferred, preferred.opponent()]
+            .into_iter()
+            .find_map(|team| self.free_starting_cell(team).map(|cell| (team, cell)))?;
+        Some(self.add_player_to_team(cell, stats, team))
+    }
+
+    /// Ajoute un joueur avec les caractéristiques de son personnage
+    ///
+    /// Le joueur rejoint l'équipe qui compte le moins de combattants.
+    #[allow(dead_code)]
+    pub fn add_player_with_stats(&mut self, position: Position, stats: Stats) -> PlayerId {
+        let team = self.smallest_team();
+        self.add_player_to_team(position, stats, team)
+    }
+
+    /// Ajoute un joueur dans une équipe donnée
+    ///
+    /// Pendant le placement, la frise est recalculée avec le nouveau venu ;
+    /// une fois le combat commencé, il rejoint la fin de la frise.
+    pub fn add_player_to_team(&mut self, position: Position, stats: Stats, team: Team) -> PlayerId {
         let player_id = self.player_counter;
         self.player_counter += 1;
 
-        let player = PlayerState::new(player_id, position);
+        let mut player = PlayerState::with_stats(player_id, position, stats);
+        player.team = team;
         self.world_state.players.push(player);
 
+        if self.world_state.phase == FightPhase::Placement {
+            self.world_state.timeline = timeline::build(&self.world_state.players);
+        } else {
+            self.world_state.timeline.push(player_id);
+            self.participants.push((player_id, team));
+        }
+        self.actions.push(FightAction::Join {
+            position,
+            stats,
+            team,
+        });
+
         player_id
     }
 
     /// Retire un joueur du jeu
+    ///
+    /// Si c'était son tour, la main passe au combattant suivant de la frise.
+    /// Pendant le placement, le combat commence si tous les joueurs restants
+    /// sont prêts.
     pub fn remove_player(&mut self, player_id: PlayerId) -> bool {
-        if let Some(pos) = self
+        let Some(pos) = self
             .world_state
             .players
             .iter()
             .position(|p| p.id == player_id)
-        {
-            self.world_state.players.remove(pos);
-            true
-        } else {
-            false
+        else {
+            return false;
+        };
+
+        // Les invocations du joueur ne lui survivent pas
+        self.kill_summons(player_id);
+        self.world_state.players.remove(pos);
+        let timeline_index = self
+            .world_state
+            .timeline
+            .iter()
+            .position(|&id| id == player_id);
+        if let Some(index) = timeline_index {
+            self.world_state.timeline.remove(index);
+            if self.world_state.current_turn == player_id {
+                self.events.push(GameEvent::TurnEnded { player_id });
+                // Le suivant a pris la place du joueur retiré dans la frise
+                self.advance_turn(index);
+            }
         }
+        self.actions.push(FightAction::Leave { player_id });
+        self.start_fight_if_ready();
+        self.check_fight_end();
+        true
     }
 
-    /// Déplace un joueur vers une nouvelle position
-    pub fn move_player(&mut self, player_id: PlayerId, target: Position) -> Result<(), String> {
-        // Vérifie d'abord les contraintes avant de modifier
-        let (current_pos, movement_points, map_width, map_height) = {
-            let player = self
-                .world_state
-                .get_player(player_id)
-                .ok_or_else(|| "Joueur introuvable".to_string())?;
+    /// Déplace un joueur vers une case par le plus court chemin libre
+    pub fn move_player(&mut self, player_id: PlayerId, target: Position) -> Result<(), GameError> {
+        self.check_turn(player_id)?;
+        let (current_pos, _) = self.check_can_move(player_id)?;
 
-            if !player.is_alive {
-                return Err("Le joueur est mort".to_string());
-            }
+        // Vérifie la destination
+        if !self.is_valid_position(&target) {
+            return Err(GameError::InvalidPosition { position: target });
+        }
+        if !self.map.is_walkable(target) {
+            return Err(GameError::CellNotWalkable { position: target });
+        }
+        if self.is_position_occupied(&target, Some(player_id)) {
+            return Err(GameError::CellOccupied { position: target });
+        }
 
-            let distance = player.position.manhattan_distance(&target);
-            if distance > player.movement_points as i32 {
-                return Err("Pas assez de PM".to_string());
-            }
+        let path = pathfinding::find_path(&self.map, current_pos, target, |cell| {
+            self.is_position_occupied(&cell, Some(player_id))
+        })
+        .ok_or(GameError::NoPath { target })?;
+        self.move_along(player_id, path)
+    }
 
-            (
-                player.position,
-                player.movement_points,
-                self.world_state.map_width,
-                self.world_state.map_height,
-            )
-        };
+    /// Déplace un joueur le long d'un chemin, position de départ incluse
+    ///
+    /// Chaque pas doit mener à une case voisine, praticable et libre, et coûte
+    /// un PM. Quitter une case au contact d'ennemis coûte en plus une part des
+    /// PA et PM (voir `combat::tackle_loss`) ; faute de PM, le joueur s'arrête.
+    /// Le chemin réellement parcouru est diffusé aux clients.
+    pub fn move_along(
+        &mut self,
+        player_id: PlayerId,
+        path: Vec<Position>,
+    ) -> Result<(), GameError> {
+        self.check_turn(player_id)?;
+        let (current_pos, movement_points) = self.check_can_move(player_id)?;
+
+        if path.first() != Some(&current_pos) {
+            return Err(GameError::InvalidPath);
+        }
+        for step in path.windows(2) {
+            let cell = step[1];
+            if step[0].manhattan_distance(&cell) != 1 {
+                return Err(GameError::InvalidPath);
+            }
+            if !self.is_valid_position(&cell) {
+                return Err(GameError::InvalidPosition { position: cell });
+            }
+            if !self.map.is_walkable(cell) {
+                return Err(GameError::CellNotWalkable { position: cell });
+            }
+            if self.is_position_occupied(&cell, Some(player_id)) {
+                return Err(GameError::CellOccupied { position: cell });
+            }
+        }
 
-        // Vérifie la position
-        if target.x < 0 || target.x >= map_width || target.y < 0 || target.y >= map_height {
-            return Err("Position invalide".to_string());
+        let cost = pathfinding::path_cost(&path);
+        if cost > movement_points {
+            return Err(GameError::NotEnoughMovementPoints {
+                required: cost,
+                available: movement_points,
+            });
         }
 
-        // Vérifie si la position est occupée
-        let is_occupied = self
+        // Le chemin est valide : le joueur le suit, taclé à chaque case quittée
+        // au contact d'ennemis, jusqu'à ne plus avoir de PM
+        let (team, dodge, mut action_points) = self
             .world_state
-            .players
-            .iter()
-            .any(|p| p.position == target && p.is_alive && p.id != player_id);
-
-        if is_occupied {
-            return Err("Position occupée".to_string());
+            .get_player(player_id)
+            .map(|p| (p.team, p.stats.dodge(), p.action_points))
+            .ok_or(GameError::PlayerNotFound { player_id })?;
+        let mut movement_points = movement_points;
+        let mut lost = TackleLoss::default();
+        let mut walked = 1;
+        for &cell in &path[..path.len() - 1] {
+            let tacklers = self.adjacent_enemy_tackles(team, cell);
+            let lost_movement_points = combat::tackle_loss(dodge, &tacklers, movement_points);
+            let lost_action_points = combat::tackle_loss(dodge, &tacklers, action_points);
+            movement_points -= lost_movement_points;
+            action_points -= lost_action_points;
+            lost.movement_points += lost_movement_points;
+            lost.action_points += lost_action_points;
+
+            if movement_points == 0 {
+                break;
+            }
+            movement_points -= 1;
+            walked += 1;
         }
+        let walked_path = path[..walked].to_vec();
 
-        // Maintenant modifie le joueur
         let player = self
             .world_state
             .get_player_mut(player_id)
-            .ok_or_else(|| "Joueur introuvable".to_string())?;
-
-        let distance = current_pos.manhattan_distance(&target);
-        player.position = target;
-        player.movement_points = movement_points.saturating_sub(distance as u32);
+            .ok_or(GameError::PlayerNotFound { player_id })?;
+        player.position = walked_path[walked_path.len() - 1];
+        player.movement_points = movement_points;
+        player.action_points = action_points;
+
+        self.events.push(GameEvent::Moved {
+            player_id,
+            path: walked_path,
+            tackle: (lost != TackleLoss::default()).then_some(lost),
+        });
+        self.push_ap_mp_changed(player_id);
+        self.actions.push(FightAction::Move { player_id, path });
 
         Ok(())
     }
 
+    /// Tacle des ennemis vivants d'une équipe adjacents à une case
+    fn adjacent_enemy_tackles(&self, team: Team, cell: Position) -> Vec<i32> {
+        self.world_state
+            .players
+            .iter()
+            .filter(|p| p.is_alive && p.team != team)
+            .filter(|p| p.position.manhattan_distance(&cell) == 1)
+            .map(|p| p.stats.tackle())
+            .collect()
+    }
+
+    /// Vérifie qu'un joueur peut se déplacer, et retourne sa position et ses PM
+    fn check_can_move(&self, player_id: PlayerId) -> Result<(Position, u32), GameError> {
+        let player = self
+            .world_state
+            .get_player(player_id)
+            .ok_or(GameError::PlayerNotFound { player_id })?;
+
+        if !player.is_alive {
+            return Err(GameError::PlayerDead { player_id });
+        }
+        if player.is_rooted() {
+            return Err(GameError::Rooted { player_id });
+        }
+        Ok((player.position, player.movement_points))
+    }
+
     /// Gère une attaque entre deux joueurs
-    pub fn attack(&mut self, attacker_id: PlayerId, target_id: PlayerId) -> Result<u32, String> {
+    pub fn attack(&mut self, attacker_id: PlayerId, target_id: PlayerId) -> Result<u32, GameError> {
+        self.check_turn(attacker_id)?;
         if attacker_id == target_id {
-            return Err("Ne peut pas s'attaquer soi-même".to_string());
+            return Err(GameError::CannotTargetSelf);
         }
 
         // Clone les données nécessaires pour éviter les conflits de borrow
         let attacker_position = self
             .world_state
             .get_player(attacker_id)
-            .ok_or_else(|| "Attaquant introuvable".to_string())
-            .map(|p| (p.position, p.is_alive, p.action_points))?;
+            .ok_or(GameError::PlayerNotFound {
+                player_id: attacker_id,
+            })
+            .map(|p| (p.position, p.is_alive, p.action_points, p.team))?;
 
         if !attacker_position.1 {
-            return Err("L'attaquant est mort".to_string());
+            return Err(GameError::PlayerDead {
+                player_id: attacker_id,
+            });
         }
 
         if attacker_position.2 == 0 {
-            return Err("Pas assez de PA".to_string());
+            return Err(GameError::NotEnoughActionPoints {
+                required: 1,
+                available: 0,
+            });
         }
 
         let target = self
             .world_state
             .get_player_mut(target_id)
-            .ok_or_else(|| "Cible introuvable".to_string())?;
+            .ok_or(GameError::TargetNotFound { target_id })?;
 
         if !target.is_alive {
-            return Err("La cible est déjà morte".to_string());
+            return Err(GameError::TargetAlreadyDead { target_id });
+        }
+
+        if target.team == attacker_position.3 {
+            return Err(GameError::FriendlyFire { target_id });
         }
 
         let distance = attacker_position.0.manhattan_distance(&target.position);
         if distance > 1 {
-            return Err("Cible hors de portée".to_string());
+            return Err(GameError::OutOfRange {
+                distance: distance as u32,
+                max_range: 1,
+            });
         }
 
-        // Calcul des dégâts (simple pour l'instant)
-        let damage = 25;
-        target.health = target.health.saturating_sub(damage);
-
-        if target.health == 0 {
-            target.is_alive = false;
-        }
+        // Attaque au corps à corps : 25 dégâts de terre
+        let damage = self.elemental_damage(attacker_id, target_id, Element::Earth, 25, 25);
+        let damage = self.inflict_damage(attacker_id, target_id, damage);
 
         // Consomme les PA de l'attaquant
         if let Some(attacker_mut) = self.world_state.get_player_mut(attacker_id) {
             attacker_mut.action_points -= 1;
         }
+        self.push_ap_mp_changed(attacker_id);
+        self.actions.push(FightAction::Attack {
+            attacker_id,
+            target_id,
+        });
+        self.check_fight_end();
 
         Ok(damage)
     }
 
-    /// Termine le tour d'un joueur et passe au suivant
-    pub fn end_turn(&mut self, player_id: PlayerId) -> Result<(), String> {
-        if self.world_state.current_turn != player_id {
-            return Err("Ce n'est pas votre tour".to_string());
+    /// Lance un sort sur une case
+    ///
+    /// Le lancer est validé contre l'état du lanceur (tour de jeu, sort connu,
+    /// PA, relance, lancers du tour) et la carte (case valide et praticable,
+    /// portée, ligne, ligne de vue). Les effets s'appliquent à chaque joueur
+    /// vivant présent dans la zone du sort (voir `AreaShape::cells`),
+    /// effet par effet.
+    /// Un coup critique ajoute le bonus critique du sort à ses dégâts de base.
+    pub fn cast_spell(
+        &mut self,
+        caster_id: PlayerId,
+        spell_id: SpellId,
+        target_cell: Position,
+    ) -> Result<(), GameError> {
+        self.check_turn(caster_id)?;
+        let caster = self
+            .world_state
+            .get_player(caster_id)
+            .ok_or(GameError::PlayerNotFound {
+                player_id: caster_id,
+            })?;
+
+        if !caster.is_alive {
+            return Err(GameError::PlayerDead {
+                player_id: caster_id,
+            });
         }
 
-        if self.world_state.get_player(player_id).is_none() {
-            return Err("Joueur introuvable".to_string());
+        let (spell, known) = spells::get(spell_id)
+            .zip(caster.known_spell(spell_id))
+            .ok_or(GameError::UnknownSpell { spell_id })?;
+
+        if known.cooldown_remaining > 0 {
+            return Err(GameError::SpellOnCooldown {
+                spell_id,
+                turns_remaining: known.cooldown_remaining,
+            });
         }
 
-        // Passe au joueur suivant
-        let alive_players: Vec<PlayerId> = self
-            .world_state
-            .players
-            .iter()
-            .filter(|p| p.is_alive)
-            .map(|p| p.id)
+        if let Some(max_casts) = spell.casts_per_turn {
+            if known.casts_this_turn >= max_casts {
+                return Err(GameError::CastLimitReached {
+                    spell_id,
+                    max_casts,
+                });
+            }
+        }
+
+        if caster.action_points < spell.ap_cost {
+            return Err(GameError::NotEnoughActionPoints {
+                required: spell.ap_cost,
+                available: caster.action_points,
+            });
+        }
+
+        if !self.is_valid_position(&target_cell) {
+            return Err(GameError::InvalidPosition {
+                position: target_cell,
+            });
+        }
+        if !self.map.is_walkable(target_cell) {
+            return Err(GameError::CellNotWalkable {
+                position: target_cell,
+            });
+        }
+
+        spell
+            .check_range(caster.position, target_cell)
+            .map_err(|e| match e {
+                RangeError::TooFar { distance } => GameError::OutOfRange {
+                    distance,
+                    max_range: spell.max_range,
+                },
+                RangeError::TooClose { distance } => GameError::TooClose {
+                    distance,
+                    min_range: spell.min_range,
+                },
+                RangeError::NotInLine => GameError::NotInLine { target_cell },
+            })?;
+
+        let caster_position = caster.position;
+        if spell.line_of_sight
+            && !line_of_sight::has_line_of_sight(&self.map, caster_position, target_cell, |cell| {
+                self.is_position_occupied(&cell, None)
+            })
+        {
+            return Err(GameError::NoLineOfSight { target_cell });
+        }
+        // La case d'une téléportation ou d'une invocation doit être libre
+        if spell.effects.iter().any(SpellEffect::targets_cell)
+            && self.is_position_occupied(&target_cell, None)
+        {
+            return Err(GameError::CellOccupied {
+                position: target_cell,
+            });
+        }
+
+        // Le lancer est valide : consomme les PA et enregistre le lancer
+        if let Some(caster) = self.world_state.get_player_mut(caster_id) {
+            caster.action_points -= spell.ap_cost;
+            caster.record_cast(spell);
+        }
+        self.events.push(GameEvent::SpellCast {
+            caster_id,
+            spell_id,
+            target_cell,
+        });
+
+        let critical = self.rng.chance(spell.critical_chance);
+        if critical {
+            self.events.push(GameEvent::CriticalHit {
+                caster_id,
+                spell_id,
+            });
+        }
+        let bonus = if critical { spell.critical_bonus } else { 0 };
+
+        let caster_team = self.world_state.get_player(caster_id).map(|p| p.team);
+        // Les combattants touchés sont repérés avant que les effets ne les déplacent
+        let targets: Vec<(PlayerId, bool)> = spell
+            .area
+            .cells(caster_position, target_cell)
+            .into_iter()
+            .filter_map(|cell| {
+                self.world_state
+                    .players
+                    .iter()
+                    .find(|p| p.position == cell && p.is_alive)
+            })
+            .map(|p| (p.id, Some(p.team) == caster_team))
             .collect();
+        for effect in spell.effects {
+            // Téléportation et invocations visent la case, qu'il y ait une cible ou non
+            match *effect {
+                SpellEffect::Teleport => {
+                    self.teleport(caster_id, target_cell);
+                    continue;
+                }
+                SpellEffect::Summon { creature } => {
+                    self.summon(caster_id, creature, target_cell);
+                    continue;
+                }
+                _ => {}
+            }
+            // Les effets nuisibles épargnent les alliés du lanceur, lui compris
+            for &(target_id, is_ally) in &targets {
+                if is_ally && effect.is_hostile() {
+                    continue;
+                }
+                self.apply_spell_effect(caster_id, target_id, spell_id, effect, bonus);
+            }
+        }
+
+        self.push_ap_mp_changed(caster_id);
+        self.actions.push(FightAction::CastSpell {
+            caster_id,
+            spell_id,
+            target_cell,
+        });
+        self.check_fight_end();
+
+        Ok(())
+    }
+
+    /// Applique un effet de sort à un joueur
+    ///
+    /// `critical_bonus` s'ajoute aux dégâts de base des effets de dégâts. Les
+    /// poussées et attirances suivent l'axe dominant entre le lanceur et la
+    /// cible (voir `area::direction`).
+    fn apply_spell_effect(
+        &mut self,
+        caster_id: PlayerId,
+        target_id: PlayerId,
+        spell_id: SpellId,
+        effect: &SpellEffect,
+        critical_bonus: u32,
+    ) {
+        match *effect {
+            SpellEffect::Damage { element, min, max } => {
+                let damage = self.elemental_damage(
+                    caster_id,
+                    target_id,
+                    element,
+                    min + critical_bonus,
+                    max + critical_bonus,
+                );
+                self.inflict_damage(caster_id, target_id, damage);
+            }
+            SpellEffect::RemoveActionPoints { amount } => {
+                self.remove_points(caster_id, target_id, amount, 0);
+            }
+            SpellEffect::RemoveMovementPoints { amount } => {
+                self.remove_points(caster_id, target_id, 0, amount);
+            }
+            SpellEffect::Heal { amount } => {
+                let Some(target) = self.world_state.get_player_mut(target_id) else {
+                    return;
+                };
+                if !target.is_alive {
+                    return;
+                }
+                let healed = amount.min(target.max_health - target.health);
+                target.health += healed;
+                self.events.push(GameEvent::Healed {
+                    target_id,
+                    source_id: caster_id,
+                    amount: healed,
+                });
+            }
+            SpellEffect::ApplyStatus {
+                status,
+                duration,
+                dispellable,
+            } => {
+                let Some(target) = self.world_state.get_player_mut(target_id) else {
+                    return;
+                };
+                if !target.is_alive {
+                    return;
+                }
+                let effect = ActiveEffect {
+                    kind: status,
+                    caster_id,
+                    spell_id,
+                    turns_remaining: duration,
+                    dispellable,
+                };
+                target.effects.push(effect.clone());
+
+                // Les effets de PA/PM s'appliquent aussi au tour en cours
+                let points_changed = match status {
+                    StatusKind::ActionPoints { amount } => {
+                        target.action_points = target.action_points.saturating_add_signed(amount);
+                        true
+                    }
+                    StatusKind::MovementPoints { amount } => {
+                        target.movement_points =
+                            target.movement_points.saturating_add_signed(amount);
+                        true
+                    }
+                    _ => false,
+                };
+                self.events
+                    .push(GameEvent::StatusApplied { target_id, effect });
+                if points_changed {
+                    self.push_ap_mp_changed(target_id);
+                }
+            }
+            SpellEffect::Dispel => {
+                let dispelled = self
+                    .world_state
+                    .get_player_mut(target_id)
+                    .is_some_and(|target| target.is_alive && target.dispel());
+                if dispelled {
+                    self.events.push(GameEvent::StatusDispelled {
+                        target_id,
+                        source_id: caster_id,
+                    });
+                }
+            }
+            SpellEffect::Push { distance } => {
+                if let Some(direction) = self.direction_between(caster_id, target_id) {
+                    self.slide(caster_id, target_id, direction, distance, true);
+                }
+            }
+            SpellEffect::Pull { distance } => {
+                if let Some((dx, dy)) = self.direction_between(caster_id, target_id) {
+                    self.slide(caster_id, target_id, (-dx, -dy), distance, false);
+                }
+            }
+            SpellEffect::SwapPositions => {
+                if target_id == caster_id {
+                    return;
+                }
+                let caster = self.world_state.get_player(caster_id).map(|p| p.position);
+                let target = self.world_state.get_player(target_id).map(|p| p.position);
+                let (Some(caster_position), Some(target_position)) = (caster, target) else {
+                    return;
+                };
+                if let Some(caster) = self.world_state.get_player_mut(caster_id) {
+                    caster.position = target_position;
+                }
+                if let Some(target) = self.world_state.get_player_mut(target_id) {
+                    target.position = caster_position;
+                }
+                self.events.push(GameEvent::Swapped {
+                    first_id: caster_id,
+                    second_id: target_id,
+                });
+            }
+            // Appliqués par `cast_spell`, qui connaît la case ciblée
+            SpellEffect::Teleport | SpellEffect::Summon { .. } => {}
+        }
+    }
+
+    /// Direction d'une poussée du lanceur vers la cible
+    fn direction_between(&self, caster_id: PlayerId, target_id: PlayerId) -> Option<(i32, i32)> {
+        let caster = self.world_state.get_player(caster_id)?;
+        let target = self.world_state.get_player(target_id)?;
+        area::direction(caster.position, target.position)
+    }
 
-        if let Some(current_pos) = alive_players.iter().position(|&id| id == player_id) {
-            let next_pos = (current_pos + 1) % alive_players.len();
-            self.world_state.current_turn = alive_players[next_pos];
-            self.world_state.turn_number += 1;
+    /// Déplace de force un joueur de `distance` cases dans une direction
+    ///
+    /// Le joueur s'arrête devant une case impraticable, hors de la carte ou
+    /// occupée, sans être taclé. Si `collisions` est vrai, la poussée
+    /// restante se transforme en dégâts (voir `combat::collision_damage`),
+    /// dont le combattant heurté subit la moitié.
+    fn slide(
+        &mut self,
+        source_id: PlayerId,
+        target_id: PlayerId,
+        (dx, dy): (i32, i32),
+        distance: u32,
+        collisions: bool,
+    ) {
+        let Some(target) = self.world_state.get_player(target_id) else {
+            return;
+        };
+        if !target.is_alive {
+            return;
+        }
 
-            // Réinitialise les PA/PM du nouveau joueur
-            if let Some(next_player) = self
+        let mut path = vec![target.position];
+        let mut remaining = distance;
+        let mut hit = None;
+        while remaining > 0 {
+            let current = path[path.len() - 1];
+            let next = Position::new(current.x + dx, current.y + dy);
+            if !self.map.is_walkable(next) {
+                break;
+            }
+            hit = self
                 .world_state
-                .get_player_mut(self.world_state.current_turn)
-            {
-                next_player.reset_turn();
+                .players
+                .iter()
+                .find(|p| p.position == next && p.is_alive)
+                .map(|p| p.id);
+            if hit.is_some() {
+                break;
             }
+            path.push(next);
+            remaining -= 1;
         }
 
-        Ok(())
+        if path.len() > 1 {
+            if let Some(target) = self.world_state.get_player_mut(target_id) {
+                target.position = path[path.len() - 1];
+            }
+            self.events.push(GameEvent::Pushed {
+                player_id: target_id,
+                path,
+            });
+        }
+        if collisions && remaining > 0 {
+            let damage = combat::collision_damage(remaining);
+            self.inflict_damage(source_id, target_id, damage);
+            if let Some(hit_id) = hit {
+                self.inflict_damage(source_id, hit_id, damage / 2);
+            }
+        }
     }
 
-    /// Vérifie si une position est valide (dans les limites de la carte)
-    #[allow(dead_code)]
-    fn is_valid_position(&self, pos: &Position) -> bool {
-        pos.x >= 0
-            && pos.x < self.world_state.map_width
-            && pos.y >= 0
-            && pos.y < self.world_state.map_height
+    /// Invoque une créature sur une case, vérifiée libre par `cast_spell`
+    ///
+    /// L'invocation rejoint l'équipe de son invocateur et joue juste après lui.
+    fn summon(&mut self, summoner_id: PlayerId, creature_id: CreatureId, position: Position) {
+        let (Some(summoner), Some(creature)) = (
+            self.world_state.get_player(summoner_id),
+            summons::get(creature_id),
+        ) else {
+            return;
+        };
+        let team = summoner.team;
+        let summon_id = self.player_counter;
+        self.player_counter += 1;
+        self.participants.push((summon_id, team));
+
+        self.world_state.add_summon(PlayerState::summoned(
+            summon_id,
+            position,
+            team,
+            summoner_id,
+            creature,
+        ));
+        self.events.push(GameEvent::Summoned {
+            summoner_id,
+            summon_id,
+            creature_id,
+            position,
+        });
     }
 
-    /// Vérifie si une position est occupée par un autre joueur
-    #[allow(dead_code)]
-    fn is_position_occupied(&self, pos: &Position, exclude_id: Option<PlayerId>) -> bool {
-        self.world_state
+    /// Tue les invocations vivantes d'un combattant, et les leurs à leur tour
+    fn kill_summons(&mut self, summoner_id: PlayerId) {
+        let summon_ids: Vec<PlayerId> = self
+            .world_state
             .players
             .iter()
-            .any(|p| p.position == *pos && p.is_alive && Some(p.id) != exclude_id)
+            .filter(|p| p.is_alive && p.summoner() == Some(summoner_id))
+            .map(|p| p.id)
+            .collect();
+        for summon_id in summon_ids {
+            if let Some(summon) = self.world_state.get_player_mut(summon_id) {
+                summon.health = 0;
+                summon.is_alive = false;
+            }
+            self.world_state.remove_effects_from(summon_id);
+            self.events.push(GameEvent::Died {
+                player_id: summon_id,
+            });
+            self.kill_summons(summon_id);
+        }
     }
 
-    /// Obtient l'état du monde
-    #[allow(dead_code)]
-    pub fn get_world_state(&self) -> &WorldState {
-        &self.world_state
+    /// Indique si `player_id` dirige le combattant `fighter_id`
+    ///
+    /// Un joueur dirige son personnage et ses invocations qui lui obéissent.
+    /// Chaque combattant n'agit que pendant son propre tour (voir `check_turn`).
+    pub fn controls(&self, player_id: PlayerId, fighter_id: PlayerId) -> bool {
+        fighter_id == player_id
+            || self
+                .world_state
+                .get_player(fighter_id)
+                .and_then(|fighter| fighter.summon)
+                .is_some_and(|summon| {
+                    summon.summoner_id == player_id
+                        && summons::get(summon.creature_id)
+                            .is_some_and(|creature| creature.control == Control::Summoner)
+                })
+    }
+
+    /// Indique si un combattant est une invocation dirigée par le serveur
+    fn is_server_controlled(&self, fighter_id: PlayerId) -> bool {
+        self.world_state
+            .get_player(fighter_id)
+            .and_then(|fighter| fighter.summon)
+            .and_then(|summon| summons::get(summon.creature_id))
+            .is_some_and(|creature| creature.control == Control::Server)
+    }
+
+    /// Fait jouer son tour à une invocation dirigée par le serveur
+    ///
+    /// Elle s'approche de l'ennemi vivant le plus proche, lui lance ses sorts
+    /// tant qu'elle le peut, puis termine son tour. Ses actions passent par les
+    /// mêmes méthodes que celles des joueurs et sont enregistrées dans le
+    /// journal : le combat se rejoue sans refaire réfléchir l'invocation.
+    fn play_summon_turn(&mut self, summon_id: PlayerId) {
+        let Some(summon) = self.world_state.get_player(summon_id) else {
+            return;
+        };
+        let (team, position, movement_points) =
+            (summon.team, summon.position, summon.movement_points);
+        let spell_ids: Vec<SpellId> = summon.spells.iter().map(|known| known.spell_id).collect();
+        let target = self
+            .world_state
+            .players
+            .iter()
+            .filter(|p| p.is_alive && p.team != team)
+            .min_by_key(|p| (p.position.manhattan_distance(&position), p.id))
+            .map(|p| (p.id, p.position));
+
+        if let Some((target_id, target_position)) = target {
+            // Chemin jusqu'à la case de l'ennemi, dont on retire le dernier pas
+            let path = pathfinding::find_path(&self.map, position, target_position, |cell| {
+                cell != target_position && self.is_position_occupied(&cell, Some(summon_id))
+            });
+            if let Some(mut path) = path {
+                path.pop();
+                path.truncate(movement_points as usize + 1);
+                if path.len() > 1 {
+                    let _ = self.move_along(summon_id, path);
+                }
+            }
+
+            for spell_id in spell_ids {
+                while let Some(target_position) = self
+                    .world_state
+                    .get_player(target_id)
+                    .filter(|target| target.is_alive)
+                    .map(|target| target.position)
+                {
+                    if self
+                        .cast_spell(summon_id, spell_id, target_position)
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        }
+        let _ = self.end_turn(summon_id);
+    }
+
+    /// Téléporte un joueur sur une case, vérifiée libre par `cast_spell`
+    fn teleport(&mut self, player_id: PlayerId, position: Position) {
+        let Some(player) = self.world_state.get_player_mut(player_id) else {
+            return;
+        };
+        player.position = position;
+        self.events.push(GameEvent::Teleported {
+            player_id,
+            position,
+        });
+    }
+
+    /// Tire les retraits de PA et de PM d'un effet et les applique à la cible
+    fn remove_points(
+        &mut self,
+        source_id: PlayerId,
+        target_id: PlayerId,
+        action_points: u32,
+        movement_points: u32,
+    ) {
+        let (Some(source), Some(target)) = (
+            self.world_state.get_player(source_id),
+            self.world_state.get_player(target_id),
+        ) else {
+            return;
+        };
+        if !target.is_alive {
+            return;
+        }
+
+        let (source_stats, target_stats) = (source.stats, target.stats);
+        let (current_ap, current_mp) = (target.action_points, target.movement_points);
+        let lost_ap = combat::roll_removal(
+            &mut self.rng,
+            action_points,
+            &source_stats,
+            &target_stats,
+            current_ap,
+            BASE_ACTION_POINTS,
+        );
+        let lost_mp = combat::roll_removal(
+            &mut self.rng,
+            movement_points,
+            &source_stats,
+            &target_stats,
+            current_mp,
+            BASE_MOVEMENT_POINTS,
+        );
+
+        if let Some(target) = self.world_state.get_player_mut(target_id) {
+            target.action_points -= lost_ap;
+            target.movement_points -= lost_mp;
+        }
+        self.events.push(GameEvent::ApMpRemoved {
+            target_id,
+            source_id,
+            action_points: lost_ap,
+            movement_points: lost_mp,
+            dodged: action_points + movement_points - lost_ap - lost_mp,
+        });
+    }
+
+    /// Tire et calcule les dégâts d'un élément entre deux joueurs (voir `combat`)
+    fn elemental_damage(
+        &mut self,
+        source_id: PlayerId,
+        target_id: PlayerId,
+        element: Element,
+        min: u32,
+        max: u32,
+    ) -> u32 {
+        let base_damage = combat::roll_damage(&mut self.rng, min, max);
+        let (Some(source), Some(target)) = (
+            self.world_state.get_player(source_id),
+            self.world_state.get_player(target_id),
+        ) else {
+            return 0;
+        };
+        combat::compute_damage(
+            base_damage,
+            element,
+            &source.effective_stats(),
+            &target.effective_resistances(),
+        )
+    }
+
+    /// Retire des points de vie à un joueur, et le tue s'il tombe à 0
+    ///
+    /// Un joueur invulnérable ne subit rien ; les boucliers absorbent les dégâts
+    /// avant les points de vie. Retourne les points de vie perdus.
+    fn inflict_damage(&mut self, source_id: PlayerId, target_id: PlayerId, amount: u32) -> u32 {
+        let Some(target) = self.world_state.get_player_mut(target_id) else {
+            return 0;
+        };
+        if !target.is_alive || target.is_invulnerable() {
+            return 0;
+        }
+
+        let absorbed = target.absorb_damage(amount);
+        let amount = amount - absorbed;
+        target.health = target.health.saturating_sub(amount);
+        let died = target.health == 0;
+        if died {
+            target.is_alive = false;
+        }
+
+        if absorbed > 0 {
+            self.events.push(GameEvent::ShieldAbsorbed {
+                target_id,
+                amount: absorbed,
+            });
+        }
+        self.events.push(GameEvent::Damaged {
+            target_id,
+            source_id,
+            amount,
+        });
+        if died {
+            // Les effets posés par un mort disparaissent avec lui, comme ses invocations
+            self.world_state.remove_effects_from(target_id);
+            self.events.push(GameEvent::Died {
+                player_id: target_id,
+            });
+            self.kill_summons(target_id);
+        }
+        amount
+    }
+
+    /// Inflige les dégâts des poisons actifs d'un joueur, au début de son tour
+    fn apply_poisons(&mut self, player_id: PlayerId) {
+        let Some(player) = self.world_state.get_player(player_id) else {
+            return;
+        };
+        let poisons: Vec<(PlayerId, Element, u32)> = player
+            .effects
+            .iter()
+            .filter_map(|effect| match effect.kind {
+                StatusKind::Poison { element, amount } => Some((effect.caster_id, element, amount)),
+                _ => None,
+            })
+            .collect();
+
+        for (caster_id, element, amount) in poisons {
+            let (Some(caster), Some(target)) = (
+                self.world_state.get_player(caster_id),
+                self.world_state.get_player(player_id),
+            ) else {
+                continue;
+            };
+            let damage = combat::compute_damage(
+                amount,
+                element,
+                &caster.effective_stats(),
+                &target.effective_resistances(),
+            );
+            self.inflict_damage(caster_id, player_id, damage);
+        }
+    }
+
+    /// Vérifie que c'est au tour du joueur de jouer
+    fn check_turn(&self, player_id: PlayerId) -> Result<(), GameError> {
+        self.check_fight_running()?;
+        if self.world_state.current_turn != player_id {
+            return Err(GameError::NotYourTurn {
+                current_turn: self.world_state.current_turn,
+            });
+        }
+
+        if self.world_state.get_player(player_id).is_none() {
+            return Err(GameError::PlayerNotFound { player_id });
+        }
+        Ok(())
+    }
+
+    /// Termine le tour d'un joueur et passe au suivant dans la frise
+    ///
+    /// Un joueur qui termine lui-même son tour n'est plus considéré absent.
+    pub fn end_turn(&mut self, player_id: PlayerId) -> Result<(), GameError> {
+        self.check_turn(player_id)?;
+
+        self.consecutive_timeouts.remove(&player_id);
+        self.set_afk(player_id, false);
+        self.actions.push(FightAction::EndTurn { player_id });
+        self.pass_turn(player_id);
+        Ok(())
+    }
+
+    /// Donne la main au combattant qui suit `player_id` dans la frise
+    fn pass_turn(&mut self, player_id: PlayerId) {
+        self.events.push(GameEvent::TurnEnded { player_id });
+        if let Some(index) = self
+            .world_state
+            .timeline
+            .iter()
+            .position(|&id| id == player_id)
+        {
+            self.advance_turn(index + 1);
+        }
+        self.check_fight_end();
+    }
+
+    /// Donne la main au prochain combattant vivant de la frise, à partir de `start`
+    ///
+    /// Revenir au début de la frise commence un nouveau tour de jeu. Un
+    /// combattant tué par ses poisons en début de tour passe la main à son tour.
+    fn advance_turn(&mut self, mut start: usize) {
+        self.turn_deadline = None;
+        self.world_state.turn_time_remaining = self.turn_duration.as_secs() as u32;
+        while let Some((next_id, wrapped)) =
+            timeline::next_alive(&self.world_state.timeline, &self.world_state.players, start)
+        {
+            if wrapped {
+                self.world_state.turn_number += 1;
+            }
+            self.world_state.current_turn = next_id;
+
+            // Les effets posés par le nouveau joueur perdent un tour, puis ses
+            // PA/PM sont réinitialisés avec les effets qui restent
+            self.world_state.expire_effects(next_id);
+            if let Some(next_player) = self.world_state.get_player_mut(next_id) {
+                next_player.reset_turn();
+            }
+            self.events.push(GameEvent::TurnStarted {
+                player_id: next_id,
+                turn_number: self.world_state.turn_number,
+                time_limit: self.world_state.turn_time_remaining,
+            });
+            self.push_ap_mp_changed(next_id);
+            self.apply_poisons(next_id);
+
+            if self
+                .world_state
+                .get_player(next_id)
+                .is_some_and(|p| p.is_alive)
+            {
+                return;
+            }
+            self.events
+                .push(GameEvent::TurnEnded { player_id: next_id });
+            start = self
+                .world_state
+                .timeline
+                .iter()
+                .position(|&id| id == next_id)
+                .map_or(0, |index| index + 1);
+        }
+    }
+
+    /// Vérifie si une position est valide (dans les limites de la carte)
+    fn is_valid_position(&self, pos: &Position) -> bool {
+        self.map.contains(*pos)
+    }
+
+    /// Vérifie si une position est occupée par un autre joueur
+    fn is_position_occupied(&self, pos: &Position, exclude_id: Option<PlayerId>) -> bool {
+        self.world_state
+            .players
+            .iter()
+            .any(|p| p.position == *pos && p.is_alive && Some(p.id) != exclude_id)
+    }
+
+    /// Obtient l'état du monde
+    #[allow(dead_code)]
+    pub fn get_world_state(&self) -> &WorldState {
+        &self.world_state
     }
 
     /// Obtient une copie de l'état du monde
@@ -216,6 +1594,12 @@ impl Game {
 mod tests {
     use super::*;
 
+    /// Termine le placement et oublie les événements du début du combat
+    fn start(game: &mut Game) {
+        game.start_fight().unwrap();
+        game.drain_events();
+    }
+
     #[test]
     fn test_game_creation() {
         let game = Game::new(10, 10);
@@ -255,6 +1639,7 @@ mod tests {
     fn test_move_player() {
         let mut game = Game::new(10, 10);
         let player_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
 
         let result = game.move_player(player_id, Position::new(6, 5));
         assert!(result.is_ok());
@@ -268,33 +1653,57 @@ mod tests {
     fn test_move_player_out_of_bounds() {
         let mut game = Game::new(10, 10);
         let player_id = game.add_player(Position::new(1, 1));
+        start(&mut game);
 
         // Test limite gauche (x < 0), distance de 2 donc dans les PM
         let result = game.move_player(player_id, Position::new(-1, 1));
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Position invalide");
+        assert_eq!(
+            result.unwrap_err(),
+            GameError::InvalidPosition {
+                position: Position::new(-1, 1)
+            }
+        );
 
         // Test limite haut (y < 0), distance de 2 donc dans les PM
         let result = game.move_player(player_id, Position::new(1, -1));
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Position invalide");
+        assert_eq!(
+            result.unwrap_err(),
+            GameError::InvalidPosition {
+                position: Position::new(1, -1)
+            }
+        );
 
         // Test limite droite avec un joueur près du bord
         let player2_id = game.add_player(Position::new(9, 5));
+        game.world_state.current_turn = player2_id;
         let result = game.move_player(player2_id, Position::new(10, 5));
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Position invalide");
+        assert_eq!(
+            result.unwrap_err(),
+            GameError::InvalidPosition {
+                position: Position::new(10, 5)
+            }
+        );
     }
 
     #[test]
     fn test_move_player_insufficient_pm() {
         let mut game = Game::new(10, 10);
         let player_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
 
         // Tente de se déplacer trop loin (PM = 3 par défaut)
         let result = game.move_player(player_id, Position::new(9, 5));
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Pas assez de PM");
+        assert_eq!(
+            result.unwrap_err(),
+            GameError::NotEnoughMovementPoints {
+                required: 4,
+                available: 3
+            }
+        );
     }
 
     #[test]
@@ -302,11 +1711,237 @@ mod tests {
         let mut game = Game::new(10, 10);
         let player1_id = game.add_player(Position::new(5, 5));
         let _player2_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
 
         // Tente de se déplacer sur la position du joueur 2
         let result = game.move_player(player1_id, Position::new(6, 5));
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Position occupée");
+        assert_eq!(
+            result.unwrap_err(),
+            GameError::CellOccupied {
+                position: Position::new(6, 5)
+            }
+        );
+    }
+
+    /// Petite carte avec un trou en (2, 1) et un obstacle en (2, 2)
+    fn map_with_obstacles() -> GameMap {
+        GameMap::from_layout(
+            5,
+            5,
+            "
+            r....
+            ..o..
+            ..#..
+            .....
+            ....b
+            ",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_move_player_blocked_by_holes_and_obstacles() {
+        let mut game = Game::from_map(map_with_obstacles());
+        let player_id = game.add_player(Position::new(0, 1));
+        let other_id = game.add_player(Position::new(1, 2));
+        start(&mut game);
+
+        // Le chemin passe par le trou
+        assert_eq!(
+            game.move_along(
+                player_id,
+                vec![
+                    Position::new(0, 1),
+                    Position::new(1, 1),
+                    Position::new(2, 1),
+                ]
+            ),
+            Err(GameError::CellNotWalkable {
+                position: Position::new(2, 1)
+            })
+        );
+        // La destination est un obstacle
+        game.world_state.current_turn = other_id;
+        assert_eq!(
+            game.move_player(other_id, Position::new(2, 2)),
+            Err(GameError::CellNotWalkable {
+                position: Position::new(2, 2)
+            })
+        );
+        game.world_state.current_turn = player_id;
+        // Le contournement du trou coûte plus de PM que le joueur n'en a
+        assert_eq!(
+            game.move_player(player_id, Position::new(3, 1)),
+            Err(GameError::NotEnoughMovementPoints {
+                required: 5,
+                available: 3
+            })
+        );
+        assert!(game.drain_events().is_empty());
+
+        game.move_player(player_id, Position::new(1, 1)).unwrap();
+        assert_eq!(
+            game.world_state
+                .get_player(player_id)
+                .unwrap()
+                .movement_points,
+            2
+        );
+    }
+
+    #[test]
+    fn test_move_player_goes_around_obstacles() {
+        let mut game = Game::from_map(map_with_obstacles());
+        let player_id = game.add_player(Position::new(1, 1));
+        start(&mut game);
+        game.world_state
+            .get_player_mut(player_id)
+            .unwrap()
+            .movement_points = 6;
+
+        game.move_player(player_id, Position::new(3, 1)).unwrap();
+
+        let events = game.drain_events();
+        let GameEvent::Moved { path, .. } = &events[0] else {
+            panic!("Déplacement attendu : {:?}", events[0]);
+        };
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.last(), Some(&Position::new(3, 1)));
+        assert!(path.iter().all(|&cell| game.map.is_walkable(cell)));
+        let player = game.world_state.get_player(player_id).unwrap();
+        assert_eq!(player.position, Position::new(3, 1));
+        assert_eq!(player.movement_points, 2);
+    }
+
+    #[test]
+    fn test_move_along_validates_each_step() {
+        let mut game = Game::new(10, 10);
+        let player_id = game.add_player(Position::new(0, 1));
+        let _enemy_id = game.add_player(Position::new(2, 1));
+        start(&mut game);
+
+        // Le chemin doit partir du joueur et avancer case par case
+        for path in [
+            vec![Position::new(0, 2), Position::new(0, 3)],
+            vec![Position::new(0, 1), Position::new(0, 3)],
+        ] {
+            assert_eq!(
+                game.move_along(player_id, path),
+                Err(GameError::InvalidPath)
+            );
+        }
+        // On ne traverse pas les autres joueurs
+        assert_eq!(
+            game.move_along(
+                player_id,
+                vec![
+                    Position::new(0, 1),
+                    Position::new(1, 1),
+                    Position::new(2, 1),
+                ]
+            ),
+            Err(GameError::CellOccupied {
+                position: Position::new(2, 1)
+            })
+        );
+
+        let path = vec![
+            Position::new(0, 1),
+            Position::new(0, 2),
+            Position::new(0, 3),
+            Position::new(0, 4),
+        ];
+        game.move_along(player_id, path.clone()).unwrap();
+        assert_eq!(
+            game.drain_events()[0],
+            GameEvent::Moved {
+                player_id,
+                path: path.clone(),
+                tackle: None,
+            }
+        );
+        assert_eq!(
+            game.actions().last(),
+            Some(&FightAction::Move { player_id, path })
+        );
+    }
+
+    #[test]
+    fn test_leaving_enemy_contact_is_tackled() {
+        let mut game = Game::new(10, 10);
+        let player_id = game.add_player(Position::new(5, 5));
+        let _enemy_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+
+        // Fuite et tacle nuls : la moitié des PM et des PA est perdue, et le
+        // PM restant ne suffit que pour un pas
+        game.move_player(player_id, Position::new(3, 5)).unwrap();
+
+        let events = game.drain_events();
+        assert_eq!(
+            events[0],
+            GameEvent::Moved {
+                player_id,
+                path: vec![Position::new(5, 5), Position::new(4, 5)],
+                tackle: Some(TackleLoss {
+                    movement_points: 2,
+                    action_points: 3,
+                }),
+            }
+        );
+        let player = game.world_state.get_player(player_id).unwrap();
+        assert_eq!(player.position, Position::new(4, 5));
+        assert_eq!(player.movement_points, 0);
+        assert_eq!(player.action_points, 3);
+    }
+
+    #[test]
+    fn test_allies_and_agile_players_are_not_tackled() {
+        let mut game = Game::new(10, 10);
+        let agile_id = game.add_player_with_stats(
+            Position::new(5, 5),
+            Stats {
+                agility: 60,
+                ..Default::default()
+            },
+        );
+        let _enemy_id = game.add_player(Position::new(6, 5));
+        let ally_id = game.add_player(Position::new(5, 7));
+        let _other_enemy_id = game.add_player(Position::new(8, 8));
+        start(&mut game);
+        assert_eq!(
+            game.world_state.get_player(ally_id).unwrap().team,
+            game.world_state.get_player(agile_id).unwrap().team
+        );
+
+        game.move_player(agile_id, Position::new(5, 6)).unwrap();
+        game.move_player(agile_id, Position::new(4, 6)).unwrap();
+
+        for event in game.drain_events() {
+            if let GameEvent::Moved { tackle, .. } = event {
+                assert_eq!(tackle, None);
+            }
+        }
+        let player = game.world_state.get_player(agile_id).unwrap();
+        assert_eq!(player.movement_points, 1);
+        assert_eq!(player.action_points, 6);
+    }
+
+    #[test]
+    fn test_move_player_walled_in() {
+        let mut game = Game::new(10, 10);
+        let player_id = game.add_player(Position::new(0, 0));
+        game.add_player(Position::new(1, 0));
+        game.add_player(Position::new(0, 1));
+        start(&mut game);
+
+        assert_eq!(
+            game.move_player(player_id, Position::new(1, 1)),
+            Err(GameError::NoPath {
+                target: Position::new(1, 1)
+            })
+        );
     }
 
     #[test]
@@ -314,6 +1949,7 @@ mod tests {
         let mut game = Game::new(10, 10);
         let attacker_id = game.add_player(Position::new(5, 5));
         let target_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
 
         let initial_health = game.world_state.get_player(target_id).unwrap().health;
 
@@ -331,10 +1967,11 @@ mod tests {
     fn test_attack_self() {
         let mut game = Game::new(10, 10);
         let player_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
 
         let result = game.attack(player_id, player_id);
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Ne peut pas s'attaquer soi-même");
+        assert_eq!(result.unwrap_err(), GameError::CannotTargetSelf);
     }
 
     #[test]
@@ -342,10 +1979,17 @@ mod tests {
         let mut game = Game::new(10, 10);
         let attacker_id = game.add_player(Position::new(5, 5));
         let target_id = game.add_player(Position::new(8, 8));
+        start(&mut game);
 
         let result = game.attack(attacker_id, target_id);
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Cible hors de portée");
+        assert_eq!(
+            result.unwrap_err(),
+            GameError::OutOfRange {
+                distance: 6,
+                max_range: 1
+            }
+        );
     }
 
     #[test]
@@ -353,6 +1997,7 @@ mod tests {
         let mut game = Game::new(10, 10);
         let attacker_id = game.add_player(Position::new(5, 5));
         let target_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
 
         let initial_ap = game
             .world_state
@@ -375,14 +2020,20 @@ mod tests {
         let mut game = Game::new(10, 10);
         let player1_id = game.add_player(Position::new(5, 5));
         let player2_id = game.add_player(Position::new(3, 3));
+        start(&mut game);
 
         game.world_state.current_turn = player1_id;
 
         let result = game.end_turn(player1_id);
         assert!(result.is_ok());
 
-        // Le tour devrait passer au joueur 2
+        // Le tour devrait passer au joueur 2, dans le même tour de jeu
         assert_eq!(game.world_state.current_turn, player2_id);
+        assert_eq!(game.world_state.turn_number, 1);
+
+        // Toute la frise a joué : nouveau tour de jeu
+        game.end_turn(player2_id).unwrap();
+        assert_eq!(game.world_state.current_turn, player1_id);
         assert_eq!(game.world_state.turn_number, 2);
     }
 
@@ -391,12 +2042,18 @@ mod tests {
         let mut game = Game::new(10, 10);
         let player1_id = game.add_player(Position::new(5, 5));
         let player2_id = game.add_player(Position::new(3, 3));
+        start(&mut game);
 
         game.world_state.current_turn = player1_id;
 
         let result = game.end_turn(player2_id);
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Ce n'est pas votre tour");
+        assert_eq!(
+            result.unwrap_err(),
+            GameError::NotYourTurn {
+                current_turn: player1_id
+            }
+        );
     }
 
     #[test]
@@ -404,6 +2061,7 @@ mod tests {
         let mut game = Game::new(10, 10);
         let player1_id = game.add_player(Position::new(5, 5));
         let player2_id = game.add_player(Position::new(3, 3));
+        start(&mut game);
 
         game.world_state.current_turn = player1_id;
 
@@ -422,6 +2080,1633 @@ mod tests {
         assert_eq!(player2.movement_points, 3);
     }
 
+    #[test]
+    fn test_move_player_emits_events() {
+        let mut game = Game::new(10, 10);
+        let player_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+
+        game.move_player(player_id, Position::new(6, 6)).unwrap();
+
+        assert_eq!(
+            game.drain_events(),
+            vec![
+                GameEvent::Moved {
+                    player_id,
+                    path: vec![
+                        Position::new(5, 5),
+                        Position::new(6, 5),
+                        Position::new(6, 6)
+                    ],
+                    tackle: None,
+                },
+                GameEvent::ApMpChanged {
+                    player_id,
+                    action_points: 6,
+                    movement_points: 1,
+                },
+            ]
+        );
+        assert!(game.drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_attack_emits_damage_and_death_events() {
+        let mut game = Game::new(10, 10);
+        let attacker_id = game.add_player(Position::new(5, 5));
+        let target_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state.get_player_mut(target_id).unwrap().health = 20;
+
+        game.attack(attacker_id, target_id).unwrap();
+
+        assert_eq!(
+            game.drain_events(),
+            vec![
+                GameEvent::Damaged {
+                    target_id,
+                    source_id: attacker_id,
+                    amount: 25,
+                },
+                GameEvent::Died {
+                    player_id: target_id
+                },
+                GameEvent::ApMpChanged {
+                    player_id: attacker_id,
+                    action_points: 5,
+                    movement_points: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_end_turn_emits_turn_events() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(3, 3));
+        start(&mut game);
+        game.world_state.current_turn = player1_id;
+
+        game.end_turn(player1_id).unwrap();
+
+        assert_eq!(
+            game.drain_events(),
+            vec![
+                GameEvent::TurnEnded {
+                    player_id: player1_id
+                },
+                GameEvent::TurnStarted {
+                    player_id: player2_id,
+                    turn_number: 1,
+                    time_limit: 30,
+                },
+                GameEvent::ApMpChanged {
+                    player_id: player2_id,
+                    action_points: 6,
+                    movement_points: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_replay_matches_server_state() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(7, 5));
+        start(&mut game);
+        game.world_state.current_turn = player1_id;
+        let mut replica = game.get_world_state_clone();
+
+        game.move_player(player1_id, Position::new(6, 5)).unwrap();
+        game.attack(player1_id, player2_id).unwrap();
+        game.cast_spell(player1_id, spells::PRESSION, Position::new(7, 5))
+            .unwrap();
+        game.end_turn(player1_id).unwrap();
+        game.cast_spell(player2_id, spells::MOT_SOIGNANT, Position::new(7, 5))
+            .unwrap();
+
+        for event in game.drain_events() {
+            replica.apply_event(&event);
+        }
+
+        let server_state = game.get_world_state();
+        assert_eq!(replica.current_turn, server_state.current_turn);
+        assert_eq!(replica.turn_number, server_state.turn_number);
+        for player in &server_state.players {
+            let replicated = replica.get_player(player.id).unwrap();
+            assert_eq!(replicated.position, player.position);
+            assert_eq!(replicated.health, player.health);
+            assert_eq!(replicated.action_points, player.action_points);
+            assert_eq!(replicated.movement_points, player.movement_points);
+            assert_eq!(replicated.spells, player.spells);
+        }
+    }
+
+    #[test]
+    fn test_cast_spell_damages_target() {
+        // Graine sans coup critique au premier lancer
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        let target_id = game.add_player(Position::new(8, 5));
+        start(&mut game);
+
+        game.cast_spell(caster_id, spells::FLECHE_MAGIQUE, Position::new(8, 5))
+            .unwrap();
+
+        let caster = game.world_state.get_player(caster_id).unwrap();
+        assert_eq!(caster.action_points, 2);
+        assert_eq!(
+            caster
+                .known_spell(spells::FLECHE_MAGIQUE)
+                .unwrap()
+                .casts_this_turn,
+            1
+        );
+        let events = game.drain_events();
+        let damage = match events.as_slice() {
+            [GameEvent::SpellCast {
+                spell_id: spells::FLECHE_MAGIQUE,
+                ..
+            }, GameEvent::Damaged {
+                target_id: damaged_id,
+                amount,
+                ..
+            }, GameEvent::ApMpChanged {
+                action_points: 2, ..
+            }] if *damaged_id == target_id => *amount,
+            other => panic!("Unexpected events: {:?}", other),
+        };
+        // Flèche magique : 13 à 17 dégâts de feu, sans intelligence ni résistance
+        assert!((13..=17).contains(&damage));
+        assert_eq!(
+            game.world_state.get_player(target_id).unwrap().health,
+            100 - damage
+        );
+    }
+
+    #[test]
+    fn test_attack_uses_strength_and_earth_resistance() {
+        let mut game = Game::new(10, 10);
+        let attacker_id = game.add_player_with_stats(
+            Position::new(5, 5),
+            Stats {
+                strength: 100,
+                ..Default::default()
+            },
+        );
+        let target_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state
+            .get_player_mut(target_id)
+            .unwrap()
+            .resistances
+            .earth
+            .percent = 20;
+
+        // 25 doublés par la force, puis - 20 %
+        assert_eq!(game.attack(attacker_id, target_id), Ok(40));
+        assert_eq!(game.world_state.get_player(target_id).unwrap().health, 60);
+    }
+
+    #[test]
+    fn test_add_player_with_vitality() {
+        let mut game = Game::new(10, 10);
+        let player_id = game.add_player_with_stats(
+            Position::new(5, 5),
+            Stats {
+                vitality: 30,
+                ..Default::default()
+            },
+        );
+
+        let player = game.world_state.get_player(player_id).unwrap();
+        assert_eq!(player.max_health, 130);
+        assert_eq!(player.health, 130);
+    }
+
+    #[test]
+    fn test_cast_spell_on_empty_cell() {
+        // Graine sans coup critique au premier lancer
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+
+        game.cast_spell(caster_id, spells::FLECHE_MAGIQUE, Position::new(5, 8))
+            .unwrap();
+
+        assert_eq!(
+            game.drain_events(),
+            vec![
+                GameEvent::SpellCast {
+                    caster_id,
+                    spell_id: spells::FLECHE_MAGIQUE,
+                    target_cell: Position::new(5, 8),
+                },
+                GameEvent::ApMpChanged {
+                    player_id: caster_id,
+                    action_points: 2,
+                    movement_points: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cast_spell_on_unwalkable_cell() {
+        let mut game = Game::from_map(map_with_obstacles());
+        let caster_id = game.add_player(Position::new(2, 4));
+        start(&mut game);
+
+        for cell in [Position::new(2, 2), Position::new(2, 1)] {
+            assert_eq!(
+                game.cast_spell(caster_id, spells::FLECHE_MAGIQUE, cell),
+                Err(GameError::CellNotWalkable { position: cell })
+            );
+        }
+        assert_eq!(
+            game.world_state
+                .get_player(caster_id)
+                .unwrap()
+                .action_points,
+            6
+        );
+    }
+
+    #[test]
+    fn test_cast_spell_needs_line_of_sight() {
+        let mut game = Game::from_map_with_seed(map_with_obstacles(), 0);
+        let caster_id = game.add_player(Position::new(2, 4));
+        let _ally_id = game.add_player(Position::new(3, 4));
+        start(&mut game);
+
+        // L'obstacle en (2, 2), puis un combattant, cachent la cible
+        for cell in [Position::new(2, 0), Position::new(4, 4)] {
+            assert_eq!(
+                game.cast_spell(caster_id, spells::FLECHE_MAGIQUE, cell),
+                Err(GameError::NoLineOfSight { target_cell: cell })
+            );
+        }
+        // Racines se lance sans ligne de vue
+        game.cast_spell(caster_id, spells::RACINES, Position::new(2, 0))
+            .unwrap();
+        assert!(matches!(
+            game.drain_events()[0],
+            GameEvent::SpellCast { .. }
+        ));
+    }
+
+    #[test]
+    fn test_cast_unknown_spell() {
+        let mut game = Game::new(10, 10);
+        let caster_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+
+        assert_eq!(
+            game.cast_spell(caster_id, 999, Position::new(6, 5)),
+            Err(GameError::UnknownSpell { spell_id: 999 })
+        );
+
+        // Un sort du catalogue que le personnage ne connaît pas est refusé aussi
+        game.world_state
+            .get_player_mut(caster_id)
+            .unwrap()
+            .spells
+            .clear();
+        assert_eq!(
+            game.cast_spell(caster_id, spells::PRESSION, Position::new(6, 5)),
+            Err(GameError::UnknownSpell {
+                spell_id: spells::PRESSION
+            })
+        );
+    }
+
+    #[test]
+    fn test_cast_spell_dead_caster() {
+        let mut game = Game::new(10, 10);
+        let caster_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+        game.world_state.get_player_mut(caster_id).unwrap().is_alive = false;
+
+        assert_eq!(
+            game.cast_spell(caster_id, spells::PRESSION, Position::new(6, 5)),
+            Err(GameError::PlayerDead {
+                player_id: caster_id
+            })
+        );
+    }
+
+    #[test]
+    fn test_actions_refused_outside_own_turn() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state.current_turn = player2_id;
+
+        let not_your_turn = GameError::NotYourTurn {
+            current_turn: player2_id,
+        };
+        assert_eq!(
+            game.cast_spell(player1_id, spells::PRESSION, Position::new(6, 5)),
+            Err(not_your_turn.clone())
+        );
+        assert_eq!(
+            game.attack(player1_id, player2_id),
+            Err(not_your_turn.clone())
+        );
+        assert_eq!(
+            game.move_player(player1_id, Position::new(5, 6)),
+            Err(not_your_turn)
+        );
+        let player1 = game.world_state.get_player(player1_id).unwrap();
+        assert_eq!(player1.action_points, 6);
+        assert_eq!(player1.position, Position::new(5, 5));
+        assert!(game.drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_turn_passes_on_when_current_player_leaves() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(6, 5));
+        game.add_player(Position::new(7, 5));
+        start(&mut game);
+        // À initiative égale, le premier arrivé commence
+        assert_eq!(game.world_state.current_turn, player1_id);
+
+        game.remove_player(player1_id);
+        assert_eq!(game.world_state.current_turn, player2_id);
+        assert!(game.move_player(player2_id, Position::new(6, 6)).is_ok());
+    }
+
+    #[test]
+    fn test_cast_spell_not_enough_action_points() {
+        let mut game = Game::new(10, 10);
+        let caster_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+        game.world_state
+            .get_player_mut(caster_id)
+            .unwrap()
+            .action_points = 3;
+
+        assert_eq!(
+            game.cast_spell(caster_id, spells::FLECHE_MAGIQUE, Position::new(8, 5)),
+            Err(GameError::NotEnoughActionPoints {
+                required: 4,
+                available: 3
+            })
+        );
+        assert!(game.drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_cast_spell_range() {
+        let mut game = Game::new(10, 10);
+        let caster_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+
+        assert_eq!(
+            game.cast_spell(caster_id, spells::FLECHE_MAGIQUE, Position::new(9, 8)),
+            Err(GameError::OutOfRange {
+                distance: 7,
+                max_range: 6
+            })
+        );
+        assert_eq!(
+            game.cast_spell(caster_id, spells::FLECHE_MAGIQUE, Position::new(5, 6)),
+            Err(GameError::TooClose {
+                distance: 1,
+                min_range: 2
+            })
+        );
+        assert_eq!(
+            game.cast_spell(caster_id, spells::FLECHE_MAGIQUE, Position::new(5, 10)),
+            Err(GameError::InvalidPosition {
+                position: Position::new(5, 10)
+            })
+        );
+    }
+
+    #[test]
+    fn test_cast_spell_in_line() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+
+        assert_eq!(
+            game.cast_spell(caster_id, spells::EPEE_CELESTE, Position::new(6, 6)),
+            Err(GameError::NotInLine {
+                target_cell: Position::new(6, 6)
+            })
+        );
+        assert!(game
+            .cast_spell(caster_id, spells::EPEE_CELESTE, Position::new(5, 7))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_cast_spell_limit_per_turn() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state
+            .get_player_mut(caster_id)
+            .unwrap()
+            .action_points = 12;
+
+        for _ in 0..2 {
+            game.cast_spell(caster_id, spells::PRESSION, Position::new(6, 5))
+                .unwrap();
+        }
+        assert_eq!(
+            game.cast_spell(caster_id, spells::PRESSION, Position::new(6, 5)),
+            Err(GameError::CastLimitReached {
+                spell_id: spells::PRESSION,
+                max_casts: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_cast_spell_cooldown() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        let other_id = game.add_player(Position::new(0, 0));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::EPEE_CELESTE, Position::new(5, 7))
+            .unwrap();
+
+        // Relance de 2 tours : refusé au tour suivant, accepté celui d'après
+        for expected_remaining in [2, 1] {
+            assert_eq!(
+                game.cast_spell(caster_id, spells::EPEE_CELESTE, Position::new(5, 7)),
+                Err(GameError::SpellOnCooldown {
+                    spell_id: spells::EPEE_CELESTE,
+                    turns_remaining: expected_remaining
+                })
+            );
+            game.end_turn(caster_id).unwrap();
+            game.end_turn(other_id).unwrap();
+        }
+        assert!(game
+            .cast_spell(caster_id, spells::EPEE_CELESTE, Position::new(5, 7))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_heal_is_capped_at_max_health() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+        game.world_state.get_player_mut(caster_id).unwrap().health = 90;
+
+        game.cast_spell(caster_id, spells::MOT_SOIGNANT, Position::new(5, 5))
+            .unwrap();
+
+        assert_eq!(game.world_state.get_player(caster_id).unwrap().health, 100);
+        assert!(game.drain_events().contains(&GameEvent::Healed {
+            target_id: caster_id,
+            source_id: caster_id,
+            amount: 10,
+        }));
+    }
+
+    #[test]
+    fn test_critical_hit_adds_bonus_damage() {
+        // Épée céleste : 26 à 32 dégâts d'air, +8 en critique (15 %)
+        let critical_damage = (0..200)
+            .find_map(|seed| {
+                let mut game = Game::with_seed(10, 10, seed);
+                let caster_id = game.add_player(Position::new(5, 5));
+                let target_id = game.add_player(Position::new(5, 8));
+                start(&mut game);
+                game.cast_spell(caster_id, spells::EPEE_CELESTE, Position::new(5, 8))
+                    .unwrap();
+
+                let events = game.drain_events();
+                if !events.contains(&GameEvent::CriticalHit {
+                    caster_id,
+                    spell_id: spells::EPEE_CELESTE,
+                }) {
+                    return None;
+                }
+                let health = game.world_state.get_player(target_id).unwrap().health;
+                Some(100 - health)
+            })
+            .expect("Aucun coup critique sur 200 graines");
+
+        assert!((34..=40).contains(&critical_damage));
+    }
+
+    #[test]
+    fn test_remove_movement_points_can_be_dodged() {
+        let mut removed_counts = Vec::new();
+        for seed in 0..50 {
+            let mut game = Game::with_seed(10, 10, seed);
+            let caster_id = game.add_player(Position::new(5, 5));
+            let target_id = game.add_player(Position::new(5, 7));
+            start(&mut game);
+
+            game.cast_spell(caster_id, spells::FLECHE_IMMOBILISANTE, Position::new(5, 7))
+                .unwrap();
+
+            let target = game.world_state.get_player(target_id).unwrap();
+            let removed = BASE_MOVEMENT_POINTS - target.movement_points;
+            assert_eq!(target.action_points, BASE_ACTION_POINTS);
+            assert!(game.drain_events().contains(&GameEvent::ApMpRemoved {
+                target_id,
+                source_id: caster_id,
+                action_points: 0,
+                movement_points: removed,
+                dodged: 2 - removed,
+            }));
+            removed_counts.push(removed);
+        }
+
+        // À sagesse égale, certains retraits passent et d'autres sont esquivés
+        assert!(removed_counts.iter().any(|&removed| removed > 0));
+        assert!(removed_counts.iter().any(|&removed| removed < 2));
+    }
+
+    #[test]
+    fn test_remove_points_ignores_dead_target() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        let target_id = game.add_player(Position::new(5, 7));
+        start(&mut game);
+        game.world_state.get_player_mut(target_id).unwrap().is_alive = false;
+
+        game.cast_spell(caster_id, spells::FLECHE_IMMOBILISANTE, Position::new(5, 7))
+            .unwrap();
+
+        assert!(!game
+            .drain_events()
+            .iter()
+            .any(|event| matches!(event, GameEvent::ApMpRemoved { .. })));
+    }
+
+    #[test]
+    fn test_replay_from_seed_and_actions() {
+        let mut game = Game::with_seed(10, 10, 987654321);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player_with_stats(
+            Position::new(7, 5),
+            Stats {
+                wisdom: 30,
+                ..Default::default()
+            },
+        );
+        game.start_fight().unwrap();
+        game.move_player(player1_id, Position::new(6, 5)).unwrap();
+        game.cast_spell(
+            player1_id,
+            spells::FLECHE_RALENTISSANTE,
+            Position::new(7, 5),
+        )
+        .unwrap();
+        game.end_turn(player1_id).unwrap();
+        game.cast_spell(player2_id, spells::PRESSION, Position::new(6, 5))
+            .unwrap();
+        game.cast_spell(player2_id, spells::PRESSION, Position::new(6, 5))
+            .unwrap();
+        // Les actions refusées ne sont pas enregistrées
+        assert!(game.attack(player1_id, player1_id).is_err());
+        game.remove_player(player2_id);
+        assert_eq!(game.actions().len(), 9);
+
+        let mut replayed = Game::replay(game.map().clone(), game.seed(), game.actions()).unwrap();
+
+        assert_eq!(replayed.get_world_state(), game.get_world_state());
+        assert_eq!(replayed.drain_events(), game.drain_events());
+        assert_eq!(replayed.actions(), game.actions());
+    }
+
+    #[test]
+    fn test_poison_damages_at_target_turn_start() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        let target_id = game.add_player(Position::new(5, 8));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::FLECHE_EMPOISONNEE, Position::new(5, 8))
+            .unwrap();
+        assert_eq!(game.world_state.get_player(target_id).unwrap().health, 100);
+
+        game.end_turn(caster_id).unwrap();
+        let target = game.world_state.get_player(target_id).unwrap();
+        assert_eq!(target.health, 92);
+        assert_eq!(target.effects[0].turns_remaining, 3);
+
+        // La durée se décompte au début des tours du lanceur
+        game.end_turn(target_id).unwrap();
+        let target = game.world_state.get_player(target_id).unwrap();
+        assert_eq!(target.health, 92);
+        assert_eq!(target.effects[0].turns_remaining, 2);
+    }
+
+    #[test]
+    fn test_rooted_player_cannot_move_until_caster_turn() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        let target_id = game.add_player(Position::new(5, 8));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::RACINES, Position::new(5, 8))
+            .unwrap();
+        game.end_turn(caster_id).unwrap();
+        assert_eq!(
+            game.move_player(target_id, Position::new(6, 8)),
+            Err(GameError::Rooted {
+                player_id: target_id
+            })
+        );
+
+        game.end_turn(target_id).unwrap();
+        game.end_turn(caster_id).unwrap();
+        assert!(game.move_player(target_id, Position::new(6, 8)).is_ok());
+    }
+
+    #[test]
+    fn test_shield_absorbs_damage_before_health() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state.current_turn = player1_id;
+
+        game.cast_spell(player1_id, spells::ARMURE_TERRESTRE, Position::new(5, 5))
+            .unwrap();
+        game.end_turn(player1_id).unwrap();
+        game.drain_events();
+
+        // 25 dégâts de terre, -20 % de résistance : 20 absorbés sur 30
+        assert_eq!(game.attack(player2_id, player1_id), Ok(0));
+        let player1 = game.world_state.get_player(player1_id).unwrap();
+        assert_eq!(player1.health, 100);
+        assert!(player1.has_status(|kind| *kind == StatusKind::Shield { amount: 10 }));
+        assert!(game.drain_events().contains(&GameEvent::ShieldAbsorbed {
+            target_id: player1_id,
+            amount: 20,
+        }));
+
+        assert_eq!(game.attack(player2_id, player1_id), Ok(10));
+        let player1 = game.world_state.get_player(player1_id).unwrap();
+        assert_eq!(player1.health, 90);
+        assert!(!player1.has_status(|kind| matches!(kind, StatusKind::Shield { .. })));
+    }
+
+    #[test]
+    fn test_invulnerable_player_takes_no_damage() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state.current_turn = player1_id;
+
+        game.cast_spell(player1_id, spells::IMMUNITE, Position::new(5, 5))
+            .unwrap();
+        game.end_turn(player1_id).unwrap();
+        game.drain_events();
+
+        assert_eq!(game.attack(player2_id, player1_id), Ok(0));
+        assert_eq!(game.world_state.get_player(player1_id).unwrap().health, 100);
+        assert!(!game
+            .drain_events()
+            .iter()
+            .any(|event| matches!(event, GameEvent::Damaged { .. })));
+    }
+
+    #[test]
+    fn test_compulsion_boosts_damage_and_action_points() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state.current_turn = player1_id;
+
+        game.cast_spell(player1_id, spells::COMPULSION, Position::new(5, 5))
+            .unwrap();
+        // 6 PA - 2 pour le sort + 1 de bonus immédiat
+        assert_eq!(
+            game.world_state
+                .get_player(player1_id)
+                .unwrap()
+                .action_points,
+            5
+        );
+        // 25 dégâts + 20 %
+        assert_eq!(game.attack(player1_id, player2_id), Ok(30));
+
+        game.end_turn(player1_id).unwrap();
+        game.end_turn(player2_id).unwrap();
+        assert_eq!(
+            game.world_state
+                .get_player(player1_id)
+                .unwrap()
+                .action_points,
+            7
+        );
+    }
+
+    /// Dégâts subis par un joueur d'après les événements
+    fn damage_taken(events: &[GameEvent], player_id: PlayerId) -> Vec<u32> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::Damaged {
+                    target_id, amount, ..
+                } if *target_id == player_id => Some(*amount),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_push_moves_target_away() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        let target_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::INTIMIDATION, Position::new(6, 5))
+            .unwrap();
+
+        let events = game.drain_events();
+        assert!(events.contains(&GameEvent::Pushed {
+            player_id: target_id,
+            path: vec![
+                Position::new(6, 5),
+                Position::new(7, 5),
+                Position::new(8, 5),
+                Position::new(9, 5),
+            ],
+        }));
+        // Seuls les dégâts du sort, sans collision
+        assert_eq!(damage_taken(&events, target_id).len(), 1);
+        assert_eq!(
+            game.world_state.get_player(target_id).unwrap().position,
+            Position::new(9, 5)
+        );
+    }
+
+    #[test]
+    fn test_push_into_obstacle_deals_collision_damage() {
+        let mut game = Game::from_map_with_seed(map_with_obstacles(), 0);
+        let caster_id = game.add_player(Position::new(2, 4));
+        let target_id = game.add_player(Position::new(2, 3));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::INTIMIDATION, Position::new(2, 3))
+            .unwrap();
+
+        // L'obstacle est juste derrière : les 3 cases de poussée sont perdues
+        let events = game.drain_events();
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Pushed { .. })));
+        assert_eq!(
+            damage_taken(&events, target_id).last(),
+            Some(&combat::collision_damage(3))
+        );
+        assert_eq!(
+            game.world_state.get_player(target_id).unwrap().position,
+            Position::new(2, 3)
+        );
+    }
+
+    #[test]
+    fn test_push_into_fighter_damages_both() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(2, 5));
+        let target_id = game.add_player(Position::new(3, 5));
+        let blocker_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::INTIMIDATION, Position::new(3, 5))
+            .unwrap();
+
+        let events = game.drain_events();
+        assert!(events.contains(&GameEvent::Pushed {
+            player_id: target_id,
+            path: vec![Position::new(3, 5), Position::new(4, 5)],
+        }));
+        let collision = combat::collision_damage(2);
+        assert_eq!(damage_taken(&events, target_id).last(), Some(&collision));
+        assert_eq!(damage_taken(&events, blocker_id), vec![collision / 2]);
+    }
+
+    #[test]
+    fn test_push_spares_allies() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        game.add_player(Position::new(0, 0));
+        let ally_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::INTIMIDATION, Position::new(6, 5))
+            .unwrap();
+
+        let events = game.drain_events();
+        assert!(damage_taken(&events, ally_id).is_empty());
+        assert_eq!(
+            game.world_state.get_player(ally_id).unwrap().position,
+            Position::new(6, 5)
+        );
+    }
+
+    #[test]
+    fn test_pull_stops_next_to_caster() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(2, 5));
+        let target_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::ATTIRANCE, Position::new(6, 5))
+            .unwrap();
+        assert_eq!(
+            game.world_state.get_player(target_id).unwrap().position,
+            Position::new(3, 5)
+        );
+
+        // Attirée de moins de 3 cases, la cible s'arrête au contact sans dégâts
+        game.end_turn(caster_id).unwrap();
+        game.end_turn(target_id).unwrap();
+        game.drain_events();
+        game.world_state.get_player_mut(target_id).unwrap().position = Position::new(4, 5);
+        game.cast_spell(caster_id, spells::ATTIRANCE, Position::new(4, 5))
+            .unwrap();
+        let events = game.drain_events();
+        assert!(damage_taken(&events, target_id).is_empty());
+        assert_eq!(
+            game.world_state.get_player(target_id).unwrap().position,
+            Position::new(3, 5)
+        );
+    }
+
+    #[test]
+    fn test_swap_and_teleport() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(5, 5));
+        let target_id = game.add_player(Position::new(8, 5));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::TRANSPOSITION, Position::new(8, 5))
+            .unwrap();
+        assert_eq!(
+            game.world_state.get_player(caster_id).unwrap().position,
+            Position::new(8, 5)
+        );
+        assert_eq!(
+            game.world_state.get_player(target_id).unwrap().position,
+            Position::new(5, 5)
+        );
+
+        // On ne se téléporte pas sur un combattant
+        assert_eq!(
+            game.cast_spell(caster_id, spells::BOND, Position::new(5, 5)),
+            Err(GameError::CellOccupied {
+                position: Position::new(5, 5)
+            })
+        );
+        game.cast_spell(caster_id, spells::BOND, Position::new(7, 7))
+            .unwrap();
+        assert_eq!(
+            game.world_state.get_player(caster_id).unwrap().position,
+            Position::new(7, 7)
+        );
+
+        let replayed = Game::replay(game.map().clone(), game.seed(), game.actions()).unwrap();
+        assert_eq!(replayed.get_world_state(), game.get_world_state());
+    }
+
+    #[test]
+    fn test_area_spell_hits_every_enemy_inside() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(1, 5));
+        let target_id = game.add_player(Position::new(4, 5));
+        let ally_id = game.add_player(Position::new(4, 4));
+        let neighbour_id = game.add_player(Position::new(5, 5));
+        // Ennemi en diagonale de la case ciblée
+        let outside_id = game.add_player_to_team(Position::new(5, 6), Stats::default(), Team::Blue);
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        game.cast_spell(caster_id, spells::CROIX_DE_FEU, Position::new(4, 5))
+            .unwrap();
+
+        let events = game.drain_events();
+        assert_eq!(damage_taken(&events, target_id).len(), 1);
+        assert_eq!(damage_taken(&events, neighbour_id).len(), 1);
+        // Les alliés sont épargnés et la croix ne couvre pas les diagonales
+        assert!(damage_taken(&events, ally_id).is_empty());
+        assert!(damage_taken(&events, outside_id).is_empty());
+    }
+
+    #[test]
+    fn test_area_spell_on_empty_cell() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player(Position::new(2, 5));
+        let behind_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+        game.world_state.current_turn = caster_id;
+
+        // La ligne part de la case vide ciblée et s'étend derrière elle
+        game.cast_spell(caster_id, spells::SOUFFLE, Position::new(3, 5))
+            .unwrap();
+        let events = game.drain_events();
+        assert_eq!(damage_taken(&events, behind_id).len(), 1);
+
+        // L'anneau épargne son centre
+        game.world_state
+            .get_player_mut(caster_id)
+            .unwrap()
+            .action_points = 6;
+        game.cast_spell(caster_id, spells::ANNEAU_DE_RONCES, Position::new(5, 5))
+            .unwrap();
+        let events = game.drain_events();
+        assert!(damage_taken(&events, behind_id).is_empty());
+    }
+
+    #[test]
+    fn test_summon_plays_after_summoner_and_dies_with_him() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let summoner_id = game.add_player(Position::new(2, 5));
+        let enemy_id = game.add_player(Position::new(7, 5));
+        start(&mut game);
+        game.world_state.current_turn = summoner_id;
+
+        assert_eq!(
+            game.cast_spell(summoner_id, spells::INVOCATION_DE_TOFU, Position::new(7, 5)),
+            Err(GameError::OutOfRange {
+                distance: 5,
+                max_range: 3
+            })
+        );
+        game.cast_spell(summoner_id, spells::INVOCATION_DE_TOFU, Position::new(3, 5))
+            .unwrap();
+        let tofu = game.world_state.players.last().unwrap();
+        let tofu_id = tofu.id;
+        assert_eq!(tofu.summoner(), Some(summoner_id));
+        assert_eq!(tofu.team, Team::Red);
+        assert_eq!(
+            game.world_state.timeline,
+            vec![summoner_id, tofu_id, enemy_id]
+        );
+
+        // Le tofu obéit à son invocateur, et à lui seul
+        game.end_turn(summoner_id).unwrap();
+        assert_eq!(game.world_state.current_turn, tofu_id);
+        assert!(game.controls(summoner_id, tofu_id));
+        assert!(!game.controls(enemy_id, tofu_id));
+        game.end_turn(tofu_id).unwrap();
+        assert_eq!(game.world_state.current_turn, enemy_id);
+
+        game.drain_events();
+        game.inflict_damage(enemy_id, summoner_id, 1000);
+        game.check_fight_end();
+        let events = game.drain_events();
+        assert!(events.contains(&GameEvent::Died { player_id: tofu_id }));
+        assert!(!game.world_state.get_player(tofu_id).unwrap().is_alive);
+        assert_eq!(game.outcome().unwrap().winning_team, Team::Blue);
+    }
+
+    #[test]
+    fn test_summon_acts_only_during_its_turn() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let summoner_id = game.add_player(Position::new(2, 5));
+        let enemy_id = game.add_player(Position::new(7, 5));
+        start(&mut game);
+        game.world_state.current_turn = summoner_id;
+
+        game.cast_spell(summoner_id, spells::INVOCATION_DE_TOFU, Position::new(3, 5))
+            .unwrap();
+        let tofu_id = game.world_state.players.last().unwrap().id;
+
+        // Ni pendant le tour de son invocateur, ni pendant celui d'un ennemi
+        for current_turn in [summoner_id, enemy_id] {
+            game.world_state.current_turn = current_turn;
+            let not_your_turn = Err(GameError::NotYourTurn { current_turn });
+            assert_eq!(
+                game.move_player(tofu_id, Position::new(3, 6)),
+                not_your_turn
+            );
+            assert_eq!(
+                game.cast_spell(tofu_id, spells::SOUFFLE, Position::new(4, 5)),
+                not_your_turn
+            );
+            assert_eq!(game.end_turn(tofu_id), not_your_turn);
+        }
+
+        // Pendant son tour, c'est l'invocateur qui attend
+        game.world_state.current_turn = tofu_id;
+        assert_eq!(
+            game.move_player(summoner_id, Position::new(2, 6)),
+            Err(GameError::NotYourTurn {
+                current_turn: tofu_id
+            })
+        );
+        game.move_player(tofu_id, Position::new(3, 6)).unwrap();
+    }
+
+    #[test]
+    fn test_summons_die_when_summoner_leaves() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let summoner_id = game.add_player(Position::new(2, 5));
+        game.add_player(Position::new(7, 5));
+        game.add_player(Position::new(2, 7));
+        start(&mut game);
+        game.world_state.current_turn = summoner_id;
+
+        game.cast_spell(
+            summoner_id,
+            spells::INVOCATION_DE_BOUFTOU,
+            Position::new(3, 5),
+        )
+        .unwrap();
+        let bouftou_id = game.world_state.players.last().unwrap().id;
+
+        game.remove_player(summoner_id);
+        assert!(!game.world_state.get_player(bouftou_id).unwrap().is_alive);
+        // Le combat continue : un allié du joueur parti est toujours là
+        assert_eq!(game.outcome(), None);
+    }
+
+    #[test]
+    fn test_server_controlled_summon_plays_on_tick() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let summoner_id = game.add_player(Position::new(2, 5));
+        let enemy_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+        game.world_state.current_turn = summoner_id;
+
+        game.cast_spell(
+            summoner_id,
+            spells::INVOCATION_DE_BOUFTOU,
+            Position::new(3, 5),
+        )
+        .unwrap();
+        let bouftou_id = game.world_state.players.last().unwrap().id;
+        assert!(!game.controls(summoner_id, bouftou_id));
+        game.end_turn(summoner_id).unwrap();
+        game.drain_events();
+
+        // Le bouftou s'approche au contact et frappe deux fois, puis passe la main
+        game.tick(Instant::now());
+        assert_eq!(
+            game.world_state.get_player(bouftou_id).unwrap().position,
+            Position::new(5, 5)
+        );
+        let events = game.drain_events();
+        assert_eq!(damage_taken(&events, enemy_id).len(), 2);
+        assert_eq!(game.world_state.current_turn, enemy_id);
+
+        let replayed = Game::replay(game.map().clone(), game.seed(), game.actions()).unwrap();
+        assert_eq!(
+            replayed.get_world_state().players,
+            game.get_world_state().players
+        );
+        assert_eq!(
+            replayed.get_world_state().timeline,
+            game.get_world_state().timeline
+        );
+    }
+
+    #[test]
+    fn test_dispel_removes_dispellable_effects() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(5, 8));
+        start(&mut game);
+        game.world_state.current_turn = player2_id;
+
+        game.cast_spell(player2_id, spells::COMPULSION, Position::new(5, 8))
+            .unwrap();
+        game.cast_spell(player2_id, spells::IMMUNITE, Position::new(5, 8))
+            .unwrap();
+        game.end_turn(player2_id).unwrap();
+        game.cast_spell(player1_id, spells::DESENVOUTEMENT, Position::new(5, 8))
+            .unwrap();
+
+        let player2 = game.world_state.get_player(player2_id).unwrap();
+        assert_eq!(player2.effects.len(), 1);
+        assert!(player2.is_invulnerable());
+        assert!(game.drain_events().contains(&GameEvent::StatusDispelled {
+            target_id: player2_id,
+            source_id: player1_id,
+        }));
+    }
+
+    #[test]
+    fn test_status_events_replay_matches_server_state() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(5, 7));
+        start(&mut game);
+        game.world_state.current_turn = player1_id;
+        let mut replica = game.get_world_state_clone();
+
+        game.cast_spell(player1_id, spells::FLECHE_EMPOISONNEE, Position::new(5, 7))
+            .unwrap();
+        game.cast_spell(player1_id, spells::ARMURE_TERRESTRE, Position::new(5, 5))
+            .unwrap();
+        game.end_turn(player1_id).unwrap();
+        game.cast_spell(player2_id, spells::COMPULSION, Position::new(5, 7))
+            .unwrap();
+        game.cast_spell(
+            player2_id,
+            spells::FLECHE_IMMOBILISANTE,
+            Position::new(5, 5),
+        )
+        .unwrap();
+        game.end_turn(player2_id).unwrap();
+
+        for event in game.drain_events() {
+            replica.apply_event(&event);
+        }
+        assert_eq!(&replica, game.get_world_state());
+    }
+
+    #[test]
+    fn test_players_alternate_teams() {
+        let mut game = Game::new(10, 10);
+        let teams: Vec<Team> = (0..4)
+            .map(|x| {
+                let player_id = game.add_player(Position::new(x, 0));
+                game.world_state.get_player(player_id).unwrap().team
+            })
+            .collect();
+        assert_eq!(teams, vec![Team::Red, Team::Blue, Team::Red, Team::Blue]);
+    }
+
+    #[test]
+    fn test_timeline_follows_initiative() {
+        let mut game = Game::new(10, 10);
+        let slow_id = game.add_player(Position::new(0, 0));
+        let fast_id = game.add_player_with_stats(
+            Position::new(1, 0),
+            Stats {
+                agility: 100,
+                ..Default::default()
+            },
+        );
+        let third_id = game.add_player(Position::new(2, 0));
+        start(&mut game);
+
+        // Le plus rapide (bleu) commence, puis les équipes alternent
+        assert_eq!(game.world_state.timeline, vec![fast_id, slow_id, third_id]);
+        assert_eq!(game.world_state.current_turn, fast_id);
+
+        game.end_turn(fast_id).unwrap();
+        game.end_turn(slow_id).unwrap();
+        assert_eq!(game.world_state.current_turn, third_id);
+        assert_eq!(game.world_state.turn_number, 1);
+        game.end_turn(third_id).unwrap();
+        assert_eq!(game.world_state.current_turn, fast_id);
+        assert_eq!(game.world_state.turn_number, 2);
+    }
+
+    #[test]
+    fn test_timeline_skips_dead_players() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(0, 0));
+        let player2_id = game.add_player(Position::new(1, 0));
+        let player3_id = game.add_player(Position::new(2, 0));
+        start(&mut game);
+        game.world_state
+            .get_player_mut(player2_id)
+            .unwrap()
+            .is_alive = false;
+
+        game.end_turn(player1_id).unwrap();
+        assert_eq!(game.world_state.current_turn, player3_id);
+        // Les morts restent dans la frise
+        assert_eq!(game.world_state.timeline.len(), 3);
+    }
+
+    #[test]
+    fn test_leaving_player_passes_the_turn() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(0, 0));
+        let player2_id = game.add_player(Position::new(1, 0));
+        let player3_id = game.add_player(Position::new(2, 0));
+        start(&mut game);
+        game.end_turn(player1_id).unwrap();
+        game.drain_events();
+
+        game.remove_player(player2_id);
+        assert_eq!(game.world_state.timeline, vec![player1_id, player3_id]);
+        assert_eq!(game.world_state.current_turn, player3_id);
+        assert_eq!(game.world_state.turn_number, 1);
+        assert!(game.drain_events().contains(&GameEvent::TurnStarted {
+            player_id: player3_id,
+            turn_number: 1,
+            time_limit: 30,
+        }));
+
+        // Le départ du dernier de la frise fait commencer un nouveau tour
+        game.remove_player(player3_id);
+        assert_eq!(game.world_state.current_turn, player1_id);
+        assert_eq!(game.world_state.turn_number, 2);
+    }
+
+    #[test]
+    fn test_late_joiner_plays_at_end_of_timeline() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(0, 0));
+        let player2_id = game.add_player(Position::new(1, 0));
+        start(&mut game);
+        game.end_turn(player1_id).unwrap();
+
+        let late_id = game.add_player_with_stats(
+            Position::new(2, 0),
+            Stats {
+                agility: 500,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            game.world_state.timeline,
+            vec![player1_id, player2_id, late_id]
+        );
+        assert_eq!(game.world_state.current_turn, player2_id);
+
+        game.end_turn(player2_id).unwrap();
+        assert_eq!(game.world_state.current_turn, late_id);
+    }
+
+    #[test]
+    fn test_player_killed_by_poison_skips_turn() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(5, 8));
+        let player3_id = game.add_player(Position::new(0, 0));
+        start(&mut game);
+        game.cast_spell(player1_id, spells::FLECHE_EMPOISONNEE, Position::new(5, 8))
+            .unwrap();
+        game.world_state.get_player_mut(player2_id).unwrap().health = 5;
+
+        game.end_turn(player1_id).unwrap();
+
+        assert!(!game.world_state.get_player(player2_id).unwrap().is_alive);
+        assert_eq!(game.world_state.current_turn, player3_id);
+        let events = game.drain_events();
+        assert!(events.contains(&GameEvent::Died {
+            player_id: player2_id
+        }));
+        assert!(events.contains(&GameEvent::TurnStarted {
+            player_id: player3_id,
+            turn_number: 1,
+            time_limit: 30,
+        }));
+    }
+
+    #[test]
+    fn test_attack_ally_is_refused() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player_to_team(Position::new(5, 5), Stats::default(), Team::Red);
+        let player2_id = game.add_player_to_team(Position::new(6, 5), Stats::default(), Team::Red);
+        start(&mut game);
+
+        assert_eq!(
+            game.attack(player1_id, player2_id),
+            Err(GameError::FriendlyFire {
+                target_id: player2_id
+            })
+        );
+    }
+
+    #[test]
+    fn test_hostile_effects_spare_allies() {
+        let mut game = Game::with_seed(10, 10, 0);
+        let caster_id = game.add_player_to_team(Position::new(5, 5), Stats::default(), Team::Red);
+        let ally_id = game.add_player_to_team(Position::new(5, 7), Stats::default(), Team::Red);
+        start(&mut game);
+
+        game.cast_spell(caster_id, spells::FLECHE_RALENTISSANTE, Position::new(5, 7))
+            .unwrap();
+        let ally = game.world_state.get_player(ally_id).unwrap();
+        assert_eq!(ally.health, 100);
+        assert_eq!(ally.action_points, BASE_ACTION_POINTS);
+
+        // Les effets bénéfiques touchent les alliés
+        game.cast_spell(caster_id, spells::ARMURE_TERRESTRE, Position::new(5, 7))
+            .unwrap();
+        assert_eq!(
+            game.world_state.get_player(ally_id).unwrap().effects.len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_fight_ends_when_a_team_is_eliminated() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(6, 5));
+        let player3_id = game.add_player(Position::new(0, 0));
+        start(&mut game);
+        game.world_state.get_player_mut(player2_id).unwrap().health = 20;
+
+        game.attack(player1_id, player2_id).unwrap();
+
+        let outcome = FightOutcome {
+            winning_team: Team::Red,
+            winners: vec![player1_id, player3_id],
+            losers: vec![player2_id],
+        };
+        assert_eq!(game.outcome(), Some(&outcome));
+        assert_eq!(game.take_fight_end(), Some(outcome));
+        assert_eq!(game.take_fight_end(), None);
+        assert_eq!(game.end_turn(player1_id), Err(GameError::FightOver));
+        assert_eq!(
+            game.move_player(player3_id, Position::new(1, 0)),
+            Err(GameError::FightOver)
+        );
+    }
+
+    #[test]
+    fn test_fight_ends_when_a_team_leaves() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(5, 5));
+        let player2_id = game.add_player(Position::new(6, 5));
+        start(&mut game);
+
+        game.remove_player(player2_id);
+
+        // Le joueur parti compte parmi les vaincus
+        let outcome = game.take_fight_end().unwrap();
+        assert_eq!(outcome.winning_team, Team::Red);
+        assert_eq!(outcome.winners, vec![player1_id]);
+        assert_eq!(outcome.losers, vec![player2_id]);
+    }
+
+    #[test]
+    fn test_fight_waits_for_both_teams() {
+        let mut game = Game::new(10, 10);
+        let player_id = game.add_player(Position::new(5, 5));
+        start(&mut game);
+        game.end_turn(player_id).unwrap();
+        assert!(game.outcome().is_none());
+    }
+
+    #[test]
+    fn test_join_uses_free_starting_cells() {
+        let mut game = Game::new(10, 10);
+        let map = game.map().clone();
+        for _ in 0..8 {
+            game.join(Stats::default()).unwrap();
+        }
+
+        for player in &game.world_state.players {
+            assert!(map.starting_cells(player.team).contains(&player.position));
+        }
+        let red = game
+            .world_state
+            .players
+            .iter()
+            .filter(|p| p.team == Team::Red)
+            .count();
+        assert_eq!(red, 4);
+        // Toutes les cellules de départ sont prises
+        assert_eq!(game.join(Stats::default()), None);
+    }
+
+    #[test]
+    fn test_place_player_swaps_with_ally() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.join(Stats::default()).unwrap();
+        let _blue_id = game.join(Stats::default()).unwrap();
+        let player2_id = game.join(Stats::default()).unwrap();
+        let red_cells = game.map().starting_cells(Team::Red).to_vec();
+        game.drain_events();
+
+        // Cellule libre
+        game.place_player(player1_id, red_cells[2]).unwrap();
+        assert_eq!(
+            game.world_state.get_player(player1_id).unwrap().position,
+            red_cells[2]
+        );
+
+        // Cellule d'un allié : les deux joueurs échangent leur place
+        game.place_player(player2_id, red_cells[2]).unwrap();
+        assert_eq!(
+            game.world_state.get_player(player2_id).unwrap().position,
+            red_cells[2]
+        );
+        assert_eq!(
+            game.world_state.get_player(player1_id).unwrap().position,
+            red_cells[1]
+        );
+        assert_eq!(
+            game.drain_events()[1..],
+            [
+                GameEvent::Placed {
+                    player_id: player2_id,
+                    position: red_cells[2],
+                },
+                GameEvent::Placed {
+                    player_id: player1_id,
+                    position: red_cells[1],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_place_player_on_own_team_cells_only() {
+        let mut game = Game::new(10, 10);
+        let red_id = game.join(Stats::default()).unwrap();
+        let blue_id = game.join(Stats::default()).unwrap();
+        let blue_cell = game.map().starting_cells(Team::Blue)[0];
+
+        assert_eq!(
+            game.place_player(red_id, blue_cell),
+            Err(GameError::InvalidStartingCell {
+                position: blue_cell
+            })
+        );
+        assert_eq!(
+            game.place_player(red_id, Position::new(5, 5)),
+            Err(GameError::InvalidStartingCell {
+                position: Position::new(5, 5)
+            })
+        );
+
+        game.start_fight().unwrap();
+        assert_eq!(
+            game.place_player(blue_id, game.map().starting_cells(Team::Blue)[1]),
+            Err(GameError::NotInPlacement)
+        );
+        assert_eq!(game.start_fight(), Err(GameError::NotInPlacement));
+    }
+
+    #[test]
+    fn test_actions_refused_during_placement() {
+        let mut game = Game::new(10, 10);
+        let red_id = game.join(Stats::default()).unwrap();
+        let blue_id = game.join(Stats::default()).unwrap();
+
+        assert_eq!(
+            game.move_player(red_id, Position::new(2, 1)),
+            Err(GameError::FightNotStarted)
+        );
+        assert_eq!(
+            game.attack(red_id, blue_id),
+            Err(GameError::FightNotStarted)
+        );
+        assert_eq!(game.end_turn(red_id), Err(GameError::FightNotStarted));
+        assert_eq!(game.world_state.current_turn, 0);
+    }
+
+    #[test]
+    fn test_fight_starts_when_everyone_is_ready() {
+        let mut game = Game::new(10, 10);
+        let red_id = game.join(Stats::default()).unwrap();
+        game.set_ready(red_id, true).unwrap();
+        // Une équipe seule ne commence pas le combat
+        assert_eq!(game.world_state.phase, FightPhase::Placement);
+
+        let blue_id = game.join(Stats::default()).unwrap();
+        game.set_ready(red_id, false).unwrap();
+        game.set_ready(blue_id, true).unwrap();
+        assert_eq!(game.world_state.phase, FightPhase::Placement);
+        game.drain_events();
+
+        game.set_ready(red_id, true).unwrap();
+        assert_eq!(game.world_state.phase, FightPhase::Fighting);
+        assert_eq!(game.world_state.current_turn, red_id);
+        let events = game.drain_events();
+        assert_eq!(
+            events[..3],
+            [
+                GameEvent::ReadyChanged {
+                    player_id: red_id,
+                    ready: true,
+                },
+                GameEvent::FightStarted {
+                    timeline: vec![red_id, blue_id],
+                },
+                GameEvent::TurnStarted {
+                    player_id: red_id,
+                    turn_number: 1,
+                    time_limit: 30,
+                },
+            ]
+        );
+        assert_eq!(
+            game.set_ready(red_id, false),
+            Err(GameError::NotInPlacement)
+        );
+
+        // Le démarrage automatique se rejoue depuis le journal
+        let replayed = Game::replay(game.map().clone(), game.seed(), game.actions()).unwrap();
+        assert_eq!(replayed.get_world_state(), game.get_world_state());
+    }
+
+    #[test]
+    fn test_placement_timer_starts_fight() {
+        let mut game = Game::new(10, 10);
+        let now = Instant::now();
+        game.join(Stats::default()).unwrap();
+
+        // Le délai ne court qu'avec les deux équipes
+        game.tick(now);
+        game.tick(now + PLACEMENT_DURATION * 2);
+        assert_eq!(game.world_state.phase, FightPhase::Placement);
+
+        game.join(Stats::default()).unwrap();
+        let start = now + PLACEMENT_DURATION * 2;
+        game.tick(start);
+        game.tick(start + PLACEMENT_DURATION / 2);
+        assert_eq!(game.world_state.phase, FightPhase::Placement);
+
+        game.tick(start + PLACEMENT_DURATION);
+        assert_eq!(game.world_state.phase, FightPhase::Fighting);
+        assert_eq!(game.actions().last(), Some(&FightAction::StartFight));
+    }
+
+    #[test]
+    fn test_turn_ends_when_time_runs_out() {
+        let mut game = Game::new(10, 10);
+        game.set_turn_duration(Duration::from_secs(10));
+        let player1_id = game.add_player(Position::new(0, 0));
+        let player2_id = game.add_player(Position::new(1, 0));
+        start(&mut game);
+        assert_eq!(game.world_state.turn_time_remaining, 10);
+
+        let now = Instant::now();
+        game.tick(now);
+        game.tick(now + Duration::from_millis(3500));
+        assert_eq!(game.world_state.turn_time_remaining, 7);
+        assert_eq!(game.world_state.current_turn, player1_id);
+
+        game.tick(now + Duration::from_secs(10));
+        assert_eq!(game.world_state.current_turn, player2_id);
+        assert_eq!(game.world_state.turn_time_remaining, 10);
+        let events = game.drain_events();
+        assert_eq!(
+            events[..3],
+            [
+                GameEvent::TurnTimedOut {
+                    player_id: player1_id
+                },
+                GameEvent::TurnEnded {
+                    player_id: player1_id
+                },
+                GameEvent::TurnStarted {
+                    player_id: player2_id,
+                    turn_number: 1,
+                    time_limit: 10,
+                },
+            ]
+        );
+
+        // Le délai du nouveau tour part du tick suivant
+        game.tick(now + Duration::from_secs(15));
+        assert_eq!(game.world_state.current_turn, player2_id);
+    }
+
+    #[test]
+    fn test_consecutive_timeouts_flag_player_afk() {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(0, 0));
+        let player2_id = game.add_player(Position::new(1, 0));
+        start(&mut game);
+
+        let mut now = Instant::now();
+        for _ in 0..AFK_TIMEOUTS {
+            assert!(!game.world_state.get_player(player1_id).unwrap().afk);
+            game.tick(now);
+            now += DEFAULT_TURN_DURATION;
+            game.tick(now);
+            game.end_turn(player2_id).unwrap();
+        }
+        assert!(game.world_state.get_player(player1_id).unwrap().afk);
+        assert!(game.drain_events().contains(&GameEvent::AfkChanged {
+            player_id: player1_id,
+            afk: true,
+        }));
+
+        // Terminer son tour soi-même met fin à l'absence
+        game.end_turn(player1_id).unwrap();
+        assert!(!game.world_state.get_player(player1_id).unwrap().afk);
+
+        let replayed = Game::replay(game.map().clone(), game.seed(), game.actions()).unwrap();
+        assert_eq!(replayed.get_world_state(), game.get_world_state());
+    }
+
     #[test]
     fn test_get_world_state_clone() {
         let mut game = Game::new(10, 10);
@@ -1,33 +1,55 @@
 use crate::game::Game;
-use shared::protocol::{Message, PlayerId};
+use crate::session::SharedSessions;
+use shared::protocol::{GameError, Message, PlayerId, RequestId};
+use sqlx::PgPool;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+/// Intervalle entre deux vérifications des délais du combat
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
 /// Gère les messages reçus des clients
+///
+/// Un message enveloppé dans une `Request` reçoit une réponse portant le même
+/// identifiant de requête. Les actions de combat peuvent viser le joueur ou
+/// une invocation qu'il dirige ; le jeu vérifie ensuite que c'est bien le tour
+/// du combattant visé. Chaque requête reçoit une réponse, y compris quand elle
+/// vise un combattant d'un autre joueur ou qu'elle n'est pas gérée.
 pub async fn handle_message(
     message: Message,
     player_id: PlayerId,
     game: Arc<Mutex<Game>>,
-) -> Result<Option<Message>, String> {
+) -> Option<Message> {
+    let (request_id, message) = message.unwrap_request();
+
     match message {
         Message::Move {
             player_id: msg_player_id,
             target_position,
+            path,
         } => {
-            if msg_player_id != player_id {
-                return Err("ID joueur incorrect".to_string());
+            let mut game_guard = game.lock().await;
+            if !game_guard.controls(player_id, msg_player_id) {
+                return not_your_fighter(request_id, msg_player_id);
             }
 
-            let mut game_guard = game.lock().await;
-            match game_guard.move_player(player_id, target_position) {
-                Ok(()) => Ok(Some(Message::Response {
-                    success: true,
-                    message: "Déplacement réussi".to_string(),
-                })),
-                Err(e) => Ok(Some(Message::Response {
-                    success: false,
-                    message: e,
-                })),
+            let result = match path {
+                None => game_guard.move_player(msg_player_id, target_position),
+                Some(path) if path.last() == Some(&target_position) => {
+                    game_guard.move_along(msg_player_id, path)
+                }
+                Some(_) => Err(GameError::InvalidPath),
+            };
+            match result {
+                Ok(()) => Some(Message::Response {
+                    request_id,
+                    result: Ok("Déplacement réussi".to_string()),
+                }),
+                Err(e) => Some(Message::Response {
+                    request_id,
+                    result: Err(e),
+                }),
             }
         }
 
@@ -35,49 +57,110 @@ pub async fn handle_message(
             attacker_id,
             target_id,
         } => {
-            if attacker_id != player_id {
-                return Err("ID attaquant incorrect".to_string());
+            let mut game_guard = game.lock().await;
+            if !game_guard.controls(player_id, attacker_id) {
+                return not_your_fighter(request_id, attacker_id);
             }
 
-            let mut game_guard = game.lock().await;
             match game_guard.attack(attacker_id, target_id) {
-                Ok(damage) => Ok(Some(Message::Response {
-                    success: true,
-                    message: format!("Attaque réussie ! {} dégâts infligés", damage),
-                })),
-                Err(e) => Ok(Some(Message::Response {
-                    success: false,
-                    message: e,
-                })),
+                Ok(damage) => Some(Message::Response {
+                    request_id,
+                    result: Ok(format!("Attaque réussie ! {} dégâts infligés", damage)),
+                }),
+                Err(e) => Some(Message::Response {
+                    request_id,
+                    result: Err(e),
+                }),
+            }
+        }
+
+        Message::CastSpell {
+            caster_id,
+            spell_id,
+            target_cell,
+        } => {
+            let mut game_guard = game.lock().await;
+            if !game_guard.controls(player_id, caster_id) {
+                return not_your_fighter(request_id, caster_id);
+            }
+
+            match game_guard.cast_spell(caster_id, spell_id, target_cell) {
+                Ok(()) => Some(Message::Response {
+                    request_id,
+                    result: Ok("Sort lancé".to_string()),
+                }),
+                Err(e) => Some(Message::Response {
+                    request_id,
+                    result: Err(e),
+                }),
             }
         }
 
         Message::EndTurn {
             player_id: msg_player_id,
+        } => {
+            let mut game_guard = game.lock().await;
+            if !game_guard.controls(player_id, msg_player_id) {
+                return not_your_fighter(request_id, msg_player_id);
+            }
+
+            match game_guard.end_turn(msg_player_id) {
+                Ok(()) => Some(Message::Response {
+                    request_id,
+                    result: Ok("Tour terminé".to_string()),
+                }),
+                Err(e) => Some(Message::Response {
+                    request_id,
+                    result: Err(e),
+                }),
+            }
+        }
+
+        Message::Place {
+            player_id: msg_player_id,
+            cell,
         } => {
             if msg_player_id != player_id {
-                return Err("ID joueur incorrect".to_string());
+                return not_your_fighter(request_id, msg_player_id);
             }
 
             let mut game_guard = game.lock().await;
-            match game_guard.end_turn(player_id) {
-                Ok(()) => Ok(Some(Message::Response {
-                    success: true,
-                    message: "Tour terminé".to_string(),
-                })),
-                Err(e) => Ok(Some(Message::Response {
-                    success: false,
-                    message: e,
-                })),
+            match game_guard.place_player(player_id, cell) {
+                Ok(()) => Some(Message::Response {
+                    request_id,
+                    result: Ok("Placement effectué".to_string()),
+                }),
+                Err(e) => Some(Message::Response {
+                    request_id,
+                    result: Err(e),
+                }),
             }
         }
 
-        Message::Connect {
-            player_id: _,
-            player_name: _,
+        Message::Ready {
+            player_id: msg_player_id,
+            ready,
         } => {
-            // La connexion est gérée dans main.rs
-            Ok(None)
+            if msg_player_id != player_id {
+                return not_your_fighter(request_id, msg_player_id);
+            }
+
+            let mut game_guard = game.lock().await;
+            match game_guard.set_ready(player_id, ready) {
+                Ok(()) => Some(Message::Response {
+                    request_id,
+                    result: Ok(if ready { "Prêt" } else { "Pas prêt" }.to_string()),
+                }),
+                Err(e) => Some(Message::Response {
+                    request_id,
+                    result: Err(e),
+                }),
+            }
+        }
+
+        Message::Connect { .. } => {
+            // La poignée de main est gérée par la session avant la boucle de jeu
+            None
         }
 
         Message::Disconnect {
@@ -87,29 +170,147 @@ pub async fn handle_message(
                 let mut game_guard = game.lock().await;
                 game_guard.remove_player(player_id);
             }
-            Ok(None)
+            None
         }
 
-        _ => Err("Message non géré".to_string()),
+        _ => Some(Message::Response {
+            request_id,
+            result: Err(GameError::UnsupportedMessage),
+        }),
     }
 }
 
-/// Broadcast l'état du monde à tous les clients
-pub async fn broadcast_world_state(
+/// Refuse une action visant un combattant que le joueur ne dirige pas
+fn not_your_fighter(request_id: Option<RequestId>, fighter_id: PlayerId) -> Option<Message> {
+    Some(Message::Response {
+        request_id,
+        result: Err(GameError::NotYourFighter { fighter_id }),
+    })
+}
+
+/// Fait avancer les délais du combat à intervalle régulier
+///
+/// Diffuse les changements provoqués par les délais : début du combat à la
+/// fin du placement, temps restant du tour et fin automatique des tours.
+pub async fn run_ticker(
     game: Arc<Mutex<Game>>,
-    broadcast_tx: tokio::sync::mpsc::UnboundedSender<(PlayerId, Message)>,
+    sessions: SharedSessions,
+    db_pool: Option<Arc<PgPool>>,
 ) {
+    let mut interval = tokio::time::interval(TICK_INTERVAL);
+    loop {
+        interval.tick().await;
+        game.lock().await.tick(Instant::now());
+        broadcast_events(game.clone(), sessions.clone()).await;
+        broadcast_world_state(game.clone(), sessions.clone()).await;
+        announce_fight_end(game.clone(), sessions.clone(), db_pool.clone()).await;
+    }
+}
+
+/// Diffuse l'état du monde à tous les clients, sous forme de deltas
+pub async fn broadcast_world_state(game: Arc<Mutex<Game>>, sessions: SharedSessions) {
     let world_state = {
         let game_guard = game.lock().await;
         game_guard.get_world_state_clone()
     };
 
-    let sync_message = Message::Sync {
-        world_state: world_state.clone(),
+    // Chaque session reçoit les changements depuis le dernier état qu'elle a reçu
+    sessions.lock().await.broadcast_state(&world_state);
+}
+
+/// Diffuse à tous les clients les événements produits par la dernière action
+pub async fn broadcast_events(game: Arc<Mutex<Game>>, sessions: SharedSessions) {
+    let events = {
+        let mut game_guard = game.lock().await;
+        game_guard.drain_events()
+    };
+
+    if !events.is_empty() {
+        sessions.lock().await.broadcast(&Message::Events { events });
+    }
+}
+
+/// Annonce la fin du combat à tous les clients et enregistre le vainqueur
+///
+/// Le vainqueur enregistré est le premier combattant de l'équipe gagnante
+/// associé à un personnage en base.
+pub async fn announce_fight_end(
+    game: Arc<Mutex<Game>>,
+    sessions: SharedSessions,
+    db_pool: Option<Arc<PgPool>>,
+) {
+    let (outcome, fight_id, winner_id) = {
+        let mut game_guard = game.lock().await;
+        let Some(outcome) = game_guard.take_fight_end() else {
+            return;
+        };
+        let winner_id = outcome
+            .winners
+            .iter()
+            .find_map(|&player_id| game_guard.character_id(player_id));
+        (outcome, game_guard.fight_id(), winner_id)
     };
 
-    // Envoie le message de synchronisation à tous les joueurs
-    for player in &world_state.players {
-        let _ = broadcast_tx.send((player.id, sync_message.clone()));
+    println!(
+        "Combat terminé : victoire de l'équipe {}",
+        outcome.winning_team
+    );
+    sessions.lock().await.broadcast(&Message::FightEnded {
+        winners: outcome.winners,
+        losers: outcome.losers,
+    });
+
+    if let (Some(pool), Some(fight_id)) = (db_pool, fight_id) {
+        if let Err(e) =
+            crate::database::queries::update_fight_status(&pool, fight_id, false, winner_id).await
+        {
+            eprintln!("⚠ Impossible d'enregistrer la fin du combat: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::protocol::Position;
+
+    fn two_player_game() -> (Arc<Mutex<Game>>, PlayerId, PlayerId) {
+        let mut game = Game::new(10, 10);
+        let player1_id = game.add_player(Position::new(0, 0));
+        let player2_id = game.add_player(Position::new(5, 5));
+        (Arc::new(Mutex::new(game)), player1_id, player2_id)
+    }
+
+    #[tokio::test]
+    async fn test_acting_for_another_player_is_answered() {
+        let (game, player1_id, player2_id) = two_player_game();
+        let message = Message::EndTurn {
+            player_id: player2_id,
+        }
+        .into_request(7);
+
+        let response = handle_message(message, player1_id, game).await;
+        assert!(matches!(
+            response,
+            Some(Message::Response {
+                request_id: Some(7),
+                result: Err(GameError::NotYourFighter { fighter_id }),
+            }) if fighter_id == player2_id
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_message_is_answered() {
+        let (game, player1_id, _) = two_player_game();
+        let message = Message::Resync.into_request(8);
+
+        let response = handle_message(message, player1_id, game).await;
+        assert!(matches!(
+            response,
+            Some(Message::Response {
+                request_id: Some(8),
+                result: Err(GameError::UnsupportedMessage),
+            })
+        ));
     }
 }
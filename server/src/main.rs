@@ -1,26 +1,35 @@
+mod combat;
 mod database;
 mod game;
 mod handler;
+mod rng;
 mod session;
+mod timeline;
+mod transport;
 
 use crate::game::Game;
-use crate::handler::broadcast_world_state;
-use crate::session::handle_client;
-use shared::protocol::{Message, PlayerId, Position};
+use crate::handler::run_ticker;
+use crate::session::{SessionRegistry, SharedSessions};
+use crate::transport::{serve_tcp, serve_websocket};
+use shared::map::GameMap;
+use sqlx::PgPool;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 
 const DEFAULT_PORT: u16 = 8080;
+const DEFAULT_WS_PORT: u16 = 8081;
 const MAP_WIDTH: i32 = 10;
 const MAP_HEIGHT: i32 = 10;
+const DEFAULT_MAP_ID: i32 = 1;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::init();
 
     // Initialise la connexion à la base de données (optionnel)
-    let _db_pool = if let Ok(database_url) = std::env::var("DATABASE_URL") {
+    let db_pool = if let Ok(database_url) = std::env::var("DATABASE_URL") {
         println!("Configuration de la base de données: {}", database_url);
         let config = database::DatabaseConfig {
             database_url,
@@ -57,27 +66,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .and_then(|p| p.parse().ok())
         .unwrap_or(DEFAULT_PORT);
 
+    let ws_port = std::env::var("WS_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(DEFAULT_WS_PORT);
+
+    let max_frame_size = std::env::var("MAX_FRAME_SIZE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(shared::framing::DEFAULT_MAX_FRAME_SIZE);
+
+    let map_id = std::env::var("MAP_ID")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_MAP_ID);
+
+    let turn_duration = std::env::var("TURN_DURATION")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(game::DEFAULT_TURN_DURATION);
+
     let addr = format!("0.0.0.0:{}", port);
     let listener = TcpListener::bind(&addr).await?;
     println!("✓ Serveur Dofus-like démarré sur {}", addr);
 
-    let game: Arc<Mutex<Game>> = Arc::new(Mutex::new(Game::new(MAP_WIDTH, MAP_HEIGHT)));
-    let (broadcast_tx, mut broadcast_rx) =
-        tokio::sync::mpsc::unbounded_channel::<(PlayerId, Message)>();
-
-    // Tâche pour gérer les broadcasts
-    let broadcast_tx_clone = broadcast_tx.clone();
-    let game_clone = game.clone();
-    tokio::spawn(async move {
-        while let Some((_player_id, message)) = broadcast_rx.recv().await {
-            // Traite les messages de broadcast
-            if let Message::Disconnect { player_id: _ } = message {
-                // Gère la déconnexion
-            }
-            // Broadcast l'état du monde après chaque action
-            broadcast_world_state(game_clone.clone(), broadcast_tx_clone.clone()).await;
-        }
-    });
+    let ws_addr = format!("0.0.0.0:{}", ws_port);
+    let ws_listener = TcpListener::bind(&ws_addr).await?;
+    println!("✓ WebSocket disponible sur ws://{}", ws_addr);
+
+    let (map, stored_map_id) = load_map(db_pool.as_deref(), map_id).await;
+    println!("✓ Carte de {}x{} cellules", map.width, map.height);
+    let mut game = Game::from_map(map);
+    if let Some(fight_id) = record_fight(db_pool.as_deref(), stored_map_id).await {
+        game.set_fight_id(fight_id);
+    }
+    game.set_turn_duration(turn_duration);
+    println!("✓ Durée des tours : {} s", turn_duration.as_secs());
+    let game: Arc<Mutex<Game>> = Arc::new(Mutex::new(game));
+    let sessions: SharedSessions = Arc::new(Mutex::new(SessionRegistry::new()));
+
+    // Délais du combat (fin du placement, fin des tours)
+    tokio::spawn(run_ticker(game.clone(), sessions.clone(), db_pool.clone()));
 
     loop {
         tokio::select! {
@@ -85,33 +115,72 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok((stream, addr)) = listener.accept() => {
                 println!("Nouvelle connexion depuis {}", addr);
                 let game_clone = game.clone();
-                let broadcast_tx_clone = broadcast_tx.clone();
+                let sessions_clone = sessions.clone();
+                let db_pool_clone = db_pool.clone();
 
                 tokio::spawn(async move {
-                    // Ajoute un nouveau joueur au jeu
-                    let player_id = {
-                        let mut game_guard = game_clone.lock().await;
-                        // Position initiale aléatoire
-                        let x = fastrand::i32(0..MAP_WIDTH);
-                        let y = fastrand::i32(0..MAP_HEIGHT);
-                        game_guard.add_player(Position::new(x, y))
-                    };
-
-                    println!("Joueur {} connecté", player_id);
-
-                    // Gère la connexion client
-                    if let Err(e) = handle_client(stream, player_id, game_clone.clone(), broadcast_tx_clone.clone()).await {
-                        eprintln!("Erreur lors de la gestion du client {}: {}", player_id, e);
+                    // Gère la connexion client, de la poignée de main à la déconnexion
+                    if let Err(e) = serve_tcp(stream, game_clone, sessions_clone, db_pool_clone, max_frame_size).await {
+                        eprintln!("Erreur lors de la gestion du client {}: {}", addr, e);
                     }
+                });
+            }
+            // Accepte les connexions WebSocket, qui partagent la même logique de session
+            Ok((stream, addr)) = ws_listener.accept() => {
+                println!("Nouvelle connexion WebSocket depuis {}", addr);
+                let game_clone = game.clone();
+                let sessions_clone = sessions.clone();
+                let db_pool_clone = db_pool.clone();
 
-                    // Retire le joueur du jeu
-                    {
-                        let mut game_guard = game_clone.lock().await;
-                        game_guard.remove_player(player_id);
+                tokio::spawn(async move {
+                    if let Err(e) = serve_websocket(stream, game_clone, sessions_clone, db_pool_clone, max_frame_size).await {
+                        eprintln!("Erreur lors de la gestion du client WebSocket {}: {}", addr, e);
                     }
-                    println!("Joueur {} déconnecté", player_id);
                 });
             }
         }
     }
 }
+
+/// Enregistre en base le combat du serveur, sur sa carte, dès son démarrage
+///
+/// Le serveur n'héberge qu'un combat : l'enregistrer avant d'accepter des
+/// joueurs lui donne un seul identifiant, même si plusieurs clients se
+/// connectent en même temps.
+async fn record_fight(db_pool: Option<&PgPool>, map_id: Option<i32>) -> Option<i32> {
+    let pool = db_pool?;
+    match database::queries::create_fight(pool, map_id, "pvp").await {
+        Ok(fight) => {
+            println!("✓ Combat {} enregistré", fight.id);
+            Some(fight.id)
+        }
+        Err(e) => {
+            eprintln!("⚠ Impossible d'enregistrer le combat: {}", e);
+            None
+        }
+    }
+}
+
+/// Charge la carte du combat depuis la base de données
+///
+/// Retourne aussi l'ID de la carte quand elle vient de la base. Sans base de
+/// données, ou si la carte est introuvable ou invalide, le combat se déroule
+/// sur une carte générée entièrement praticable.
+async fn load_map(db_pool: Option<&PgPool>, map_id: i32) -> (GameMap, Option<i32>) {
+    let Some(pool) = db_pool else {
+        return (GameMap::new(MAP_WIDTH, MAP_HEIGHT), None);
+    };
+
+    match database::queries::get_map_by_id(pool, map_id).await {
+        Ok(Some(map)) => match GameMap::try_from(&map) {
+            Ok(game_map) => {
+                println!("✓ Carte chargée : {}", map.name);
+                return (game_map, Some(map.id));
+            }
+            Err(e) => eprintln!("⚠ Carte {} invalide: {}", map.name, e),
+        },
+        Ok(None) => eprintln!("⚠ Carte {} introuvable", map_id),
+        Err(e) => eprintln!("⚠ Impossible de charger la carte: {}", e),
+    }
+    (GameMap::new(MAP_WIDTH, MAP_HEIGHT), None)
+}
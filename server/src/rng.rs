@@ -0,0 +1,96 @@
+//! Générateur aléatoire d'un combat
+//!
+//! Tous les tirages d'un combat (dégâts, coups critiques, esquives) passent
+//! par un `FightRng` initialisé avec la graine du combat. L'algorithme
+//! (SplitMix64) est implémenté ici plutôt qu'emprunté à une dépendance : la
+//! suite de tirages d'une graine ne change pas avec les mises à jour, et un
+//! combat peut être rejoué à l'identique depuis sa graine et son journal d'actions.
+
+/// Générateur SplitMix64 déterministe
+#[derive(Debug, Clone)]
+pub struct FightRng {
+    seed: u64,
+    state: u64,
+}
+
+impl FightRng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// Graine avec laquelle le générateur a été créé
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Tire un entier 64 bits uniforme
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Tire un entier entre `min` et `max` inclus
+    pub fn range(&mut self, min: u32, max: u32) -> u32 {
+        if min >= max {
+            return min;
+        }
+        let span = (max - min) as u64 + 1;
+        // Multiplication 128 bits : pas de biais notable pour des intervalles de cette taille
+        let offset = ((self.next_u64() as u128 * span as u128) >> 64) as u32;
+        min + offset
+    }
+
+    /// Réussit avec une probabilité de `percent` %
+    pub fn chance(&mut self, percent: u32) -> bool {
+        self.range(1, 100) <= percent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = FightRng::new(42);
+        let mut b = FightRng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_eq!(a.seed(), 42);
+    }
+
+    #[test]
+    fn test_sequence_is_stable() {
+        // Valeurs de référence de SplitMix64 : elles ne doivent jamais changer,
+        // sinon les combats enregistrés ne se rejouent plus à l'identique
+        let mut rng = FightRng::new(1234567);
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let mut rng = FightRng::new(7);
+        let mut seen = [false; 5];
+        for _ in 0..1000 {
+            let value = rng.range(18, 22);
+            assert!((18..=22).contains(&value));
+            seen[(value - 18) as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert_eq!(rng.range(25, 25), 25);
+    }
+
+    #[test]
+    fn test_chance_extremes() {
+        let mut rng = FightRng::new(3);
+        for _ in 0..100 {
+            assert!(!rng.chance(0));
+            assert!(rng.chance(100));
+        }
+    }
+}
@@ -1,15 +1,29 @@
-use shared::protocol::{Message, PlayerId};
+use crate::database::models::FightParticipant;
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use shared::map::GameMap;
+use shared::protocol::serialization::{deserialize_with, serialize, serialize_with, WireFormat};
+use shared::protocol::{
+    is_protocol_version_supported, Message, PlayerId, RejectionReason, Sequence, Stats, WorldDelta,
+    WorldState, SUPPORTED_PROTOCOL_VERSIONS,
+};
+use sqlx::PgPool;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Mutex};
 
 /// Gestionnaire de session pour un client connecté
-#[allow(dead_code)]
 pub struct Session {
     pub player_id: PlayerId,
     pub sender: mpsc::UnboundedSender<Message>,
+    /// Dernier état du monde envoyé au client, référence des prochains deltas
+    ///
+    /// La connexion étant ordonnée, c'est l'état que le client aura appliqué
+    /// avant de recevoir le delta suivant.
+    baseline: Option<WorldState>,
+    /// Numéro de séquence de `baseline`
+    sequence: Sequence,
 }
 
-#[allow(dead_code)]
 impl Session {
     pub fn new(player_id: PlayerId) -> (Self, mpsc::UnboundedReceiver<Message>) {
         let (tx, rx) = mpsc::unbounded_channel();
@@ -17,6 +31,8 @@ impl Session {
             Self {
                 player_id,
                 sender: tx,
+                baseline: None,
+                sequence: 0,
             },
             rx,
         )
@@ -28,92 +44,398 @@ impl Session {
             .send(message)
             .map_err(|_| "Impossible d'envoyer le message".to_string())
     }
+
+    /// Fait d'un état complet la nouvelle référence et retourne sa séquence
+    fn reset_baseline(&mut self, world_state: &WorldState) -> Sequence {
+        self.sequence += 1;
+        self.baseline = Some(world_state.clone());
+        self.sequence
+    }
+
+    /// Envoie les changements depuis le dernier état envoyé, s'il y en a
+    fn send_delta(&mut self, world_state: &WorldState) -> Result<(), String> {
+        // Tant que le client n'a pas reçu d'état complet, il n'y a rien à compléter
+        let Some(baseline) = &self.baseline else {
+            return Ok(());
+        };
+
+        let delta = WorldDelta::between(baseline, world_state, self.sequence, self.sequence + 1);
+        if delta.is_empty() {
+            return Ok(());
+        }
+
+        self.send(Message::Delta { delta })?;
+        self.reset_baseline(world_state);
+        Ok(())
+    }
 }
 
-/// Gère la communication TCP avec un client
-pub async fn handle_client(
-    stream: tokio::net::TcpStream,
-    player_id: PlayerId,
-    game: Arc<tokio::sync::Mutex<crate::game::Game>>,
-    broadcast_tx: mpsc::UnboundedSender<(PlayerId, Message)>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    use crate::handler::handle_message;
-    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+/// Registre des sessions connectées, indexé par joueur
+///
+/// Chaque session possède le canal sortant lu par la tâche d'écriture de sa
+/// connexion : envoyer un message ici revient à l'envoyer au client.
+#[derive(Default)]
+pub struct SessionRegistry {
+    sessions: HashMap<PlayerId, Session>,
+}
 
-    // Envoie un message de bienvenue
-    let world_state = {
+/// Registre partagé entre les tâches de connexion
+pub type SharedSessions = Arc<Mutex<SessionRegistry>>;
+
+impl SessionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre la session d'un joueur et retourne le canal à écrire sur sa connexion
+    pub fn register(&mut self, player_id: PlayerId) -> mpsc::UnboundedReceiver<Message> {
+        let (session, rx) = Session::new(player_id);
+        self.sessions.insert(player_id, session);
+        rx
+    }
+
+    /// Retire la session d'un joueur
+    pub fn unregister(&mut self, player_id: PlayerId) -> bool {
+        self.sessions.remove(&player_id).is_some()
+    }
+
+    /// Envoie un message à un seul joueur
+    pub fn send_to(&self, player_id: PlayerId, message: Message) -> Result<(), String> {
+        self.sessions
+            .get(&player_id)
+            .ok_or_else(|| format!("Aucune session pour le joueur {}", player_id))?
+            .send(message)
+    }
+
+    /// Envoie un message à tous les joueurs connectés
+    pub fn broadcast(&self, message: &Message) {
+        for session in self.sessions.values() {
+            let _ = session.send(message.clone());
+        }
+    }
+
+    /// Envoie un message à tous les joueurs connectés sauf un
+    #[allow(dead_code)]
+    pub fn broadcast_except(&self, excluded: PlayerId, message: &Message) {
+        for session in self.sessions.values().filter(|s| s.player_id != excluded) {
+            let _ = session.send(message.clone());
+        }
+    }
+
+    /// Envoie le message de bienvenue, qui sert de premier état de référence
+    ///
+    /// `wire_format` est le format négocié, utilisé après ce message dans les deux sens.
+    pub fn send_welcome(
+        &mut self,
+        player_id: PlayerId,
+        wire_format: WireFormat,
+        world_state: &WorldState,
+        map: &GameMap,
+    ) -> Result<(), String> {
+        let session = self.get_session_mut(player_id)?;
+        let sequence = session.reset_baseline(world_state);
+        session.send(Message::Welcome {
+            player_id,
+            wire_format,
+            sequence,
+            world_state: world_state.clone(),
+            map: map.clone(),
+        })
+    }
+
+    /// Renvoie l'état complet à un client qui a détecté un trou dans la séquence
+    pub fn resync(&mut self, player_id: PlayerId, world_state: &WorldState) -> Result<(), String> {
+        let session = self.get_session_mut(player_id)?;
+        let sequence = session.reset_baseline(world_state);
+        session.send(Message::Sync {
+            sequence,
+            world_state: world_state.clone(),
+        })
+    }
+
+    /// Envoie à chaque client les changements depuis le dernier état qu'il a reçu
+    pub fn broadcast_state(&mut self, world_state: &WorldState) {
+        for session in self.sessions.values_mut() {
+            let _ = session.send_delta(world_state);
+        }
+    }
+
+    fn get_session_mut(&mut self, player_id: PlayerId) -> Result<&mut Session, String> {
+        self.sessions
+            .get_mut(&player_id)
+            .ok_or_else(|| format!("Aucune session pour le joueur {}", player_id))
+    }
+
+    /// Nombre de sessions actives
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+}
+
+/// Attend le `Connect` du client et vérifie sa version du protocole
+///
+/// Retourne le nom du joueur et le format de sérialisation retenu parmi ceux
+/// proposés par le client si la poignée de main réussit. En cas d'échec, le
+/// refus est envoyé au client et `None` est retourné.
+async fn perform_handshake<R, W, E>(
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<Option<(String, WireFormat)>, Box<dyn std::error::Error>>
+where
+    R: Stream<Item = Result<Vec<u8>, E>> + Unpin,
+    E: std::error::Error + 'static,
+    W: Sink<Vec<u8>> + Unpin,
+    W::Error: std::error::Error + 'static,
+{
+    use shared::protocol::serialization::{deserialize, peek_protocol_version};
+
+    let frame = match reader.next().await {
+        Some(frame) => frame?,
+        None => return Ok(None),
+    };
+    let bytes = &frame[..];
+
+    let reason = match deserialize(bytes) {
+        Ok(Message::Connect {
+            protocol_version,
+            client_build,
+            player_name,
+            wire_formats,
+        }) => {
+            if is_protocol_version_supported(protocol_version) {
+                let wire_format = WireFormat::negotiate(&wire_formats);
+                println!(
+                    "Poignée de main réussie pour {} (protocole {}, client {}, format {})",
+                    player_name, protocol_version, client_build, wire_format
+                );
+                return Ok(Some((player_name, wire_format)));
+            }
+            RejectionReason::UnsupportedVersion {
+                client_version: protocol_version,
+                supported_versions: SUPPORTED_PROTOCOL_VERSIONS.to_vec(),
+            }
+        }
+        // Un `Connect` d'une autre version peut ne plus se désérialiser
+        _ => match peek_protocol_version(bytes) {
+            Some(client_version) => RejectionReason::UnsupportedVersion {
+                client_version,
+                supported_versions: SUPPORTED_PROTOCOL_VERSIONS.to_vec(),
+            },
+            None => RejectionReason::HandshakeRequired,
+        },
+    };
+
+    println!("Connexion refusée : {}", reason);
+    writer
+        .send(serialize(&Message::ConnectionRejected { reason })?)
+        .await?;
+    Ok(None)
+}
+
+/// Charge les caractéristiques du personnage depuis la base, si elle est disponible
+///
+/// Retourne aussi l'ID du personnage en base. Un personnage inconnu, ou
+/// l'absence de base, donne des caractéristiques nulles.
+async fn load_character_stats(db_pool: Option<&PgPool>, player_name: &str) -> (Stats, Option<i32>) {
+    let Some(pool) = db_pool else {
+        return (Stats::default(), None);
+    };
+
+    match crate::database::queries::get_character_stats_by_name(pool, player_name).await {
+        Ok(Some(stats)) => (Stats::from(&stats), Some(stats.character_id)),
+        Ok(None) => (Stats::default(), None),
+        Err(e) => {
+            eprintln!(
+                "⚠ Impossible de charger les stats de {}: {}",
+                player_name, e
+            );
+            (Stats::default(), None)
+        }
+    }
+}
+
+/// Inscrit en base le personnage d'un joueur parmi les participants du combat, avec son équipe
+async fn record_participant(
+    game: &Mutex<crate::game::Game>,
+    db_pool: Option<&PgPool>,
+    player_id: PlayerId,
+) {
+    let Some(pool) = db_pool else {
+        return;
+    };
+    let (fight_id, character_id, team) = {
         let game_guard = game.lock().await;
-        game_guard.get_world_state_clone()
+        let team = game_guard
+            .get_world_state()
+            .get_player(player_id)
+            .map(|p| p.team);
+        (
+            game_guard.fight_id(),
+            game_guard.character_id(player_id),
+            team,
+        )
     };
+    let (Some(fight_id), Some(character_id), Some(team)) = (fight_id, character_id, team) else {
+        return;
+    };
+
+    let team = FightParticipant::team_number(team);
+    if let Err(e) =
+        crate::database::queries::add_fight_participant(pool, fight_id, character_id, team).await
+    {
+        eprintln!(
+            "⚠ Impossible d'inscrire le joueur {} au combat: {}",
+            player_id, e
+        );
+    }
+}
 
-    let welcome = Message::Welcome {
-        player_id,
-        world_state,
+/// Gère la communication avec un client, quel que soit le transport
+///
+/// `reader` fournit les messages sérialisés reçus du client, une trame par
+/// message, et `writer` envoie les trames sortantes. Le découpage en trames
+/// et ses limites de taille sont à la charge du transport (voir `transport`).
+///
+/// Le joueur n'est ajouté au jeu qu'une fois la poignée de main réussie, et
+/// retiré à la fin de la connexion ; un combat terminé refuse les nouveaux
+/// venus. Ses caractéristiques viennent de la base de données quand `db_pool`
+/// est fourni, et il y est inscrit parmi les participants du combat.
+pub async fn handle_client<R, W, E>(
+    mut reader: R,
+    mut writer: W,
+    game: Arc<Mutex<crate::game::Game>>,
+    sessions: SharedSessions,
+    db_pool: Option<Arc<PgPool>>,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    R: Stream<Item = Result<Vec<u8>, E>> + Unpin + Send + 'static,
+    E: std::error::Error + Send + 'static,
+    W: Sink<Vec<u8>> + Unpin + Send + 'static,
+    W::Error: std::error::Error + 'static,
+{
+    use crate::handler::{
+        announce_fight_end, broadcast_events, broadcast_world_state, handle_message,
     };
 
-    let welcome_bytes = shared::protocol::serialization::serialize(&welcome)?;
-    let len = welcome_bytes.len() as u32;
+    let Some((player_name, wire_format)) = perform_handshake(&mut reader, &mut writer).await?
+    else {
+        return Ok(());
+    };
+    let (stats, character_id) = load_character_stats(db_pool.as_deref(), &player_name).await;
 
-    let (mut read_stream, mut write_stream) = stream.into_split();
+    // Ajoute un nouveau joueur au jeu, sur une cellule de départ libre, tant
+    // que le combat n'est pas terminé
+    let joined = {
+        let mut game_guard = game.lock().await;
+        if game_guard.outcome().is_some() {
+            Err(RejectionReason::FightOver)
+        } else {
+            let player_id = game_guard.join(stats);
+            if let (Some(player_id), Some(character_id)) = (player_id, character_id) {
+                game_guard.set_character_id(player_id, character_id);
+            }
+            player_id.ok_or(RejectionReason::FightFull)
+        }
+    };
+    let player_id = match joined {
+        Ok(player_id) => player_id,
+        Err(reason) => {
+            println!("Connexion refusée : {}", reason);
+            writer
+                .send(serialize(&Message::ConnectionRejected { reason })?)
+                .await?;
+            return Ok(());
+        }
+    };
+    record_participant(&game, db_pool.as_deref(), player_id).await;
 
-    // Envoie le message de bienvenue
-    write_stream.write_u32_le(len).await?;
-    write_stream.write_all(&welcome_bytes).await?;
+    println!("Joueur {} connecté", player_id);
 
-    // Canal pour recevoir les messages à envoyer au client
-    let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
+    // Enregistre la session : tout ce qui est envoyé au joueur passe désormais par ce canal
+    let mut rx = sessions.lock().await.register(player_id);
+
+    // Envoie un message de bienvenue
+    let (world_state, map) = {
+        let game_guard = game.lock().await;
+        (game_guard.get_world_state_clone(), game_guard.map().clone())
+    };
+    sessions
+        .lock()
+        .await
+        .send_welcome(player_id, wire_format, &world_state, &map)?;
+
+    // Informe les autres joueurs de l'arrivée du nouveau
+    broadcast_world_state(game.clone(), sessions.clone()).await;
 
     // Tâche pour envoyer les messages au client
     let write_task = tokio::spawn(async move {
+        let mut format = WireFormat::default();
         while let Some(message) = rx.recv().await {
-            if let Ok(bytes) = shared::protocol::serialization::serialize(&message) {
-                let len = bytes.len() as u32;
-                if write_stream.write_u32_le(len).await.is_ok() {
-                    if write_stream.write_all(&bytes).await.is_err() {
-                        break;
-                    }
-                } else {
-                    break;
+            let bytes = match serialize_with(format, &message) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    eprintln!(
+                        "Erreur de sérialisation pour le joueur {}: {}",
+                        player_id, e
+                    );
+                    continue;
                 }
+            };
+            // Le `Welcome` part en bincode, la suite dans le format négocié
+            if let Message::Welcome { wire_format, .. } = &message {
+                format = *wire_format;
+            }
+            if let Err(e) = writer.send(bytes).await {
+                eprintln!("Erreur d'envoi au joueur {}: {}", player_id, e);
+                break;
             }
         }
     });
 
     // Tâche pour lire les messages du client
     let game_for_read = game.clone();
-    let broadcast_tx_for_read = broadcast_tx.clone();
-    let tx_for_read = tx.clone();
+    let sessions_for_read = sessions.clone();
+    let db_pool_for_read = db_pool.clone();
     let read_task = tokio::spawn(async move {
-        let mut buffer = vec![0u8; 4096];
-        loop {
-            match read_stream.read_u32_le().await {
-                Ok(len) => {
-                    if len as usize > buffer.len() {
-                        buffer.resize(len as usize, 0);
-                    }
-                    if read_stream
-                        .read_exact(&mut buffer[..len as usize])
+        while let Some(frame) = reader.next().await {
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(e) => {
+                    eprintln!("Trame refusée du joueur {}: {}", player_id, e);
+                    break;
+                }
+            };
+            if let Ok(message) = deserialize_with(wire_format, &frame) {
+                if let Message::Resync = message {
+                    let world_state = game_for_read.lock().await.get_world_state_clone();
+                    let _ = sessions_for_read
+                        .lock()
                         .await
-                        .is_ok()
-                    {
-                        if let Ok(message) =
-                            shared::protocol::serialization::deserialize(&buffer[..len as usize])
-                        {
-                            // Traite le message
-                            if let Ok(Some(response)) =
-                                handle_message(message.clone(), player_id, game_for_read.clone())
-                                    .await
-                            {
-                                let _ = tx_for_read.send(response);
-                            }
-                            // Broadcast le message pour synchronisation
-                            let _ = broadcast_tx_for_read.send((player_id, message));
-                        }
-                    } else {
-                        break;
-                    }
+                        .resync(player_id, &world_state);
+                    continue;
+                }
+
+                // Traite le message
+                if let Some(response) =
+                    handle_message(message, player_id, game_for_read.clone()).await
+                {
+                    let _ = sessions_for_read.lock().await.send_to(player_id, response);
                 }
-                Err(_) => break,
+                // Diffuse les changements d'état à tous les clients
+                broadcast_events(game_for_read.clone(), sessions_for_read.clone()).await;
+                broadcast_world_state(game_for_read.clone(), sessions_for_read.clone()).await;
+                announce_fight_end(
+                    game_for_read.clone(),
+                    sessions_for_read.clone(),
+                    db_pool_for_read.clone(),
+                )
+                .await;
             }
         }
     });
@@ -124,8 +446,145 @@ pub async fn handle_client(
         _ = write_task => {},
     }
 
-    // Envoie un message de déconnexion
-    let _ = broadcast_tx.send((player_id, Message::Disconnect { player_id }));
+    sessions.lock().await.unregister(player_id);
+
+    // Retire le joueur du jeu
+    {
+        let mut game_guard = game.lock().await;
+        game_guard.remove_player(player_id);
+    }
+    println!("Joueur {} déconnecté", player_id);
+
+    // Informe les joueurs restants du départ, et du tour suivant si c'était le sien
+    broadcast_events(game.clone(), sessions.clone()).await;
+    broadcast_world_state(game.clone(), sessions.clone()).await;
+    announce_fight_end(game, sessions, db_pool).await;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn end_turn(player_id: PlayerId) -> Message {
+        Message::EndTurn { player_id }
+    }
+
+    #[test]
+    fn test_register_and_send_to() {
+        let mut registry = SessionRegistry::new();
+        let mut rx1 = registry.register(1);
+        let mut rx2 = registry.register(2);
+
+        registry.send_to(1, end_turn(1)).unwrap();
+
+        assert!(matches!(
+            rx1.try_recv(),
+            Ok(Message::EndTurn { player_id: 1 })
+        ));
+        assert!(rx2.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_send_to_unknown_player() {
+        let registry = SessionRegistry::new();
+        assert!(registry.send_to(42, end_turn(42)).is_err());
+    }
+
+    #[test]
+    fn test_broadcast_reaches_everyone() {
+        let mut registry = SessionRegistry::new();
+        let mut rx1 = registry.register(1);
+        let mut rx2 = registry.register(2);
+
+        registry.broadcast(&end_turn(1));
+
+        assert!(rx1.try_recv().is_ok());
+        assert!(rx2.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_broadcast_except() {
+        let mut registry = SessionRegistry::new();
+        let mut rx1 = registry.register(1);
+        let mut rx2 = registry.register(2);
+
+        registry.broadcast_except(1, &end_turn(1));
+
+        assert!(rx1.try_recv().is_err());
+        assert!(rx2.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_broadcast_state_sends_deltas_from_baseline() {
+        let mut registry = SessionRegistry::new();
+        let mut rx = registry.register(1);
+
+        let mut world_state = WorldState::new(10, 10);
+        world_state.players.push(shared::protocol::PlayerState::new(
+            1,
+            shared::protocol::Position::new(0, 0),
+        ));
+        registry
+            .send_welcome(1, WireFormat::Bincode, &world_state, &GameMap::new(10, 10))
+            .unwrap();
+        let mut replica = match rx.try_recv() {
+            Ok(Message::Welcome {
+                sequence: 1,
+                world_state,
+                ..
+            }) => world_state,
+            other => panic!("Wrong message: {:?}", other),
+        };
+
+        // Aucun changement : aucun delta
+        registry.broadcast_state(&world_state);
+        assert!(rx.try_recv().is_err());
+
+        world_state.get_player_mut(1).unwrap().position = shared::protocol::Position::new(1, 0);
+        registry.broadcast_state(&world_state);
+        match rx.try_recv() {
+            Ok(Message::Delta { delta }) => {
+                assert_eq!(delta.base_sequence, 1);
+                assert_eq!(delta.sequence, 2);
+                replica.apply_delta(&delta);
+            }
+            other => panic!("Wrong message: {:?}", other),
+        }
+        assert_eq!(replica, world_state);
+    }
+
+    #[test]
+    fn test_resync_resets_baseline() {
+        let mut registry = SessionRegistry::new();
+        let mut rx = registry.register(1);
+        let world_state = WorldState::new(10, 10);
+
+        registry
+            .send_welcome(1, WireFormat::Bincode, &world_state, &GameMap::new(10, 10))
+            .unwrap();
+        registry.resync(1, &world_state).unwrap();
+
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(Message::Welcome { sequence: 1, .. })
+        ));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(Message::Sync { sequence: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_unregister() {
+        let mut registry = SessionRegistry::new();
+        let _rx = registry.register(1);
+        assert_eq!(registry.len(), 1);
+
+        assert!(registry.unregister(1));
+        assert!(!registry.unregister(1));
+        assert!(registry.is_empty());
+        assert!(registry.send_to(1, end_turn(1)).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Frise du combat : ordre de jeu des combattants
+//!
+//! Les combattants de chaque équipe sont classés par initiative décroissante,
+//! puis les deux équipes alternent, en commençant par celle du combattant le
+//! plus rapide. Quand une équipe n'a plus personne à placer, les combattants
+//! restants de l'autre équipe se suivent en fin de frise.
+
+use shared::protocol::{PlayerId, PlayerState, Team};
+
+/// Construit la frise à partir des combattants
+///
+/// À initiative égale, l'équipe rouge commence et les combattants gardent leur
+/// ordre d'arrivée.
+pub fn build(players: &[PlayerState]) -> Vec<PlayerId> {
+    let ranked = |team: Team| {
+        let mut fighters: Vec<&PlayerState> = players.iter().filter(|p| p.team == team).collect();
+        // Tri stable : l'ordre d'arrivée départage les égalités
+        fighters.sort_by_key(|p| std::cmp::Reverse(p.initiative()));
+        fighters
+    };
+    let red = ranked(Team::Red);
+    let blue = ranked(Team::Blue);
+
+    let best = |fighters: &[&PlayerState]| fighters.first().map(|p| p.initiative());
+    let (first, second) = if best(&blue) > best(&red) {
+        (blue, red)
+    } else {
+        (red, blue)
+    };
+
+    let mut timeline = Vec::with_capacity(players.len());
+    for index in 0..first.len().max(second.len()) {
+        for team in [&first, &second] {
+            if let Some(player) = team.get(index) {
+                timeline.push(player.id);
+            }
+        }
+    }
+    timeline
+}
+
+/// Trouve le prochain combattant vivant de la frise, en partant de `start`
+///
+/// Retourne son ID et indique si la recherche a dû revenir au début de la
+/// frise, ce qui commence un nouveau tour de jeu.
+pub fn next_alive(
+    timeline: &[PlayerId],
+    players: &[PlayerState],
+    start: usize,
+) -> Option<(PlayerId, bool)> {
+    let is_alive = |id: PlayerId| players.iter().any(|p| p.id == id && p.is_alive);
+    (0..timeline.len())
+        .map(|offset| start + offset)
+        .map(|index| (timeline[index % timeline.len()], index >= timeline.len()))
+        .find(|&(id, _)| is_alive(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::protocol::{Position, Stats};
+
+    fn fighter(id: PlayerId, team: Team, agility: i32) -> PlayerState {
+        let mut player = PlayerState::with_stats(
+            id,
+            Position::new(0, 0),
+            Stats {
+                agility,
+                ..Default::default()
+            },
+        );
+        player.team = team;
+        player
+    }
+
+    #[test]
+    fn test_teams_alternate_by_initiative() {
+        let players = vec![
+            fighter(1, Team::Red, 10),
+            fighter(2, Team::Red, 50),
+            fighter(3, Team::Blue, 80),
+            fighter(4, Team::Blue, 20),
+            fighter(5, Team::Blue, 30),
+        ];
+
+        // Le bleu le plus rapide commence, puis les équipes alternent
+        assert_eq!(build(&players), vec![3, 2, 5, 1, 4]);
+    }
+
+    #[test]
+    fn test_ties_keep_arrival_order() {
+        let players = vec![
+            fighter(1, Team::Red, 0),
+            fighter(2, Team::Blue, 0),
+            fighter(3, Team::Red, 0),
+            fighter(4, Team::Blue, 0),
+        ];
+        assert_eq!(build(&players), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_initiative_drops_with_health() {
+        let mut wounded = fighter(1, Team::Red, 100);
+        wounded.health = 25;
+        let players = vec![wounded, fighter(2, Team::Blue, 50)];
+        assert_eq!(build(&players), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_next_alive_skips_dead_and_wraps() {
+        let mut players = vec![
+            fighter(1, Team::Red, 0),
+            fighter(2, Team::Blue, 0),
+            fighter(3, Team::Red, 0),
+        ];
+        let timeline = vec![1, 2, 3];
+
+        assert_eq!(next_alive(&timeline, &players, 1), Some((2, false)));
+        players[1].is_alive = false;
+        assert_eq!(next_alive(&timeline, &players, 1), Some((3, false)));
+        assert_eq!(next_alive(&timeline, &players, 3), Some((1, true)));
+
+        for player in &mut players {
+            player.is_alive = false;
+        }
+        assert_eq!(next_alive(&timeline, &players, 0), None);
+    }
+}
@@ -0,0 +1,158 @@
+//! Transports acceptés par le serveur
+//!
+//! TCP et WebSocket transportent les mêmes messages sérialisés : chaque
+//! transport se réduit à un flux de trames entrantes et un puits de trames
+//! sortantes, confiés à `session::handle_client`. Les règles du jeu ne
+//! dépendent donc pas du transport.
+
+use crate::game::Game;
+use crate::session::{handle_client, SharedSessions};
+use futures_util::{future, SinkExt, StreamExt};
+use shared::framing::FrameCodec;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message as WsMessage};
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+/// Connexion TCP brute : trames préfixées par leur longueur (voir `shared::framing`)
+///
+/// Les trames annoncées au-delà de `max_frame_size` ferment la connexion.
+pub async fn serve_tcp(
+    stream: TcpStream,
+    game: Arc<Mutex<Game>>,
+    sessions: SharedSessions,
+    db_pool: Option<Arc<PgPool>>,
+    max_frame_size: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (read_stream, write_stream) = stream.into_split();
+    let codec = FrameCodec::new(max_frame_size);
+
+    let reader = FramedRead::new(read_stream, codec).map(|frame| frame.map(|bytes| bytes.to_vec()));
+    let writer = FramedWrite::new(write_stream, codec);
+
+    handle_client(reader, writer, game, sessions, db_pool).await
+}
+
+/// Connexion WebSocket : un message binaire par `Message` sérialisé
+///
+/// Les messages texte sont ignorés ; ping, pong et fermeture sont gérés par
+/// tungstenite. Les messages au-delà de `max_frame_size` ferment la connexion.
+pub async fn serve_websocket(
+    stream: TcpStream,
+    game: Arc<Mutex<Game>>,
+    sessions: SharedSessions,
+    db_pool: Option<Arc<PgPool>>,
+    max_frame_size: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = WebSocketConfig {
+        max_message_size: Some(max_frame_size),
+        max_frame_size: Some(max_frame_size),
+        ..Default::default()
+    };
+    let websocket = tokio_tungstenite::accept_async_with_config(stream, Some(config)).await?;
+    let (sink, stream) = websocket.split();
+
+    let reader = stream.filter_map(|message| {
+        future::ready(match message {
+            Ok(WsMessage::Binary(bytes)) => Some(Ok(bytes)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    });
+    let writer =
+        sink.with(|bytes: Vec<u8>| future::ready(Ok::<_, WsError>(WsMessage::Binary(bytes))));
+
+    handle_client(reader, writer, game, sessions, db_pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionRegistry;
+    use shared::protocol::serialization::{deserialize, serialize, WireFormat};
+    use shared::protocol::{Message, PROTOCOL_VERSION};
+    use tokio::net::TcpListener;
+
+    fn connect_message() -> Vec<u8> {
+        serialize(&Message::Connect {
+            protocol_version: PROTOCOL_VERSION,
+            client_build: "test".to_string(),
+            player_name: "Iop".to_string(),
+            wire_formats: vec![WireFormat::Bincode],
+        })
+        .unwrap()
+    }
+
+    /// Lance un serveur de test n'acceptant qu'une connexion et retourne son adresse
+    async fn spawn_server<F, Fut>(serve: F) -> std::net::SocketAddr
+    where
+        F: FnOnce(TcpStream, Arc<Mutex<Game>>, SharedSessions) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<(), Box<dyn std::error::Error>>> + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let game = Arc::new(Mutex::new(Game::new(10, 10)));
+        let sessions = Arc::new(Mutex::new(SessionRegistry::new()));
+
+        tokio::task::spawn_local(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let _ = serve(stream, game, sessions).await;
+        });
+        addr
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_websocket_handshake() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let addr = spawn_server(|stream, game, sessions| {
+                    serve_websocket(stream, game, sessions, None, 1024 * 1024)
+                })
+                .await;
+
+                let (mut websocket, _) = tokio_tungstenite::connect_async(format!("ws://{}", addr))
+                    .await
+                    .unwrap();
+                websocket
+                    .send(WsMessage::Binary(connect_message()))
+                    .await
+                    .unwrap();
+
+                match websocket.next().await {
+                    Some(Ok(WsMessage::Binary(bytes))) => {
+                        assert!(matches!(
+                            deserialize(&bytes).unwrap(),
+                            Message::Welcome { player_id: 1, .. }
+                        ));
+                    }
+                    other => panic!("Unexpected message: {:?}", other),
+                }
+            })
+            .await;
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_tcp_handshake() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let addr = spawn_server(|stream, game, sessions| {
+                    serve_tcp(stream, game, sessions, None, 1024 * 1024)
+                })
+                .await;
+
+                let stream = TcpStream::connect(addr).await.unwrap();
+                let mut framed = tokio_util::codec::Framed::new(stream, FrameCodec::default());
+                framed.send(connect_message()).await.unwrap();
+
+                let frame = framed.next().await.unwrap().unwrap();
+                assert!(matches!(
+                    deserialize(&frame).unwrap(),
+                    Message::Welcome { player_id: 1, .. }
+                ));
+            })
+            .await;
+    }
+}
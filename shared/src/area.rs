@@ -0,0 +1,189 @@
+//! Zones d'effet des sorts
+//!
+//! Une zone est centrée sur la case ciblée. Les zones orientées (ligne, cône)
+//! s'étendent dans l'axe qui va du lanceur à la cible, comme une poussée. Le
+//! serveur s'en sert pour trouver les combattants touchés, le client pour
+//! prévisualiser la zone avant de lancer le sort.
+
+use crate::protocol::Position;
+
+/// Forme de la zone d'effet d'un sort, de taille `size` cases
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AreaShape {
+    /// Uniquement la case ciblée
+    #[default]
+    Single,
+    /// Croix : la case ciblée et `size` cases dans chacune des quatre directions
+    Cross { size: u32 },
+    /// Cercle : les cases à au plus `size` cases de la cible (distance de Manhattan)
+    Circle { size: u32 },
+    /// Ligne : la case ciblée et `size` cases derrière elle, dans l'axe lanceur-cible
+    Line { size: u32 },
+    /// Cône : s'évase derrière la case ciblée sur `size` rangées, d'une case
+    /// de chaque côté par rangée
+    Cone { size: u32 },
+    /// Anneau carré : le pourtour du carré de côté `2 * size + 1` centré sur la
+    /// case ciblée, sans son intérieur
+    Ring { size: u32 },
+}
+
+impl AreaShape {
+    /// Cases de la zone lancée depuis `caster` sur `target`
+    ///
+    /// Les cases sont triées par distance à la cible puis dans l'ordre de
+    /// lecture, pour que les effets s'appliquent dans un ordre fixe. Elles ne
+    /// sont pas limitées à la carte. Lancée sur la case du lanceur, une zone
+    /// orientée se réduit à cette case.
+    pub fn cells(self, caster: Position, target: Position) -> Vec<Position> {
+        let offset = |dx: i32, dy: i32| Position::new(target.x + dx, target.y + dy);
+        let mut cells = match self {
+            AreaShape::Single => vec![target],
+            AreaShape::Cross { size } => {
+                let size = size as i32;
+                let mut cells = vec![target];
+                for distance in 1..=size {
+                    cells.extend([
+                        offset(0, -distance),
+                        offset(distance, 0),
+                        offset(0, distance),
+                        offset(-distance, 0),
+                    ]);
+                }
+                cells
+            }
+            AreaShape::Circle { size } => square(size as i32)
+                .filter(|&(dx, dy)| (dx.abs() + dy.abs()) as u32 <= size)
+                .map(|(dx, dy)| offset(dx, dy))
+                .collect(),
+            AreaShape::Ring { size } => square(size as i32)
+                .filter(|&(dx, dy)| dx.abs().max(dy.abs()) as u32 == size)
+                .map(|(dx, dy)| offset(dx, dy))
+                .collect(),
+            AreaShape::Line { size } | AreaShape::Cone { size } => {
+                let Some((dx, dy)) = direction(caster, target) else {
+                    return vec![target];
+                };
+                let widening = matches!(self, AreaShape::Cone { .. });
+                let mut cells = Vec::new();
+                for depth in 0..=size as i32 {
+                    let width = if widening { depth } else { 0 };
+                    // Le décalage latéral est perpendiculaire à la direction
+                    for side in -width..=width {
+                        cells.push(offset(dx * depth - dy * side, dy * depth + dx * side));
+                    }
+                }
+                cells
+            }
+        };
+        cells.sort_by_key(|cell| (cell.manhattan_distance(&target), cell.y, cell.x));
+        cells
+    }
+}
+
+/// Décalages du carré de côté `2 * size + 1` centré sur l'origine
+fn square(size: i32) -> impl Iterator<Item = (i32, i32)> {
+    (-size..=size).flat_map(move |dy| (-size..=size).map(move |dx| (dx, dy)))
+}
+
+/// Direction de `from` vers `to`, réduite à l'axe dominant
+///
+/// À égalité (diagonale), la direction suit l'axe horizontal. Retourne `None`
+/// si les deux positions sont confondues.
+pub fn direction(from: Position, to: Position) -> Option<(i32, i32)> {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    if dx == 0 && dy == 0 {
+        None
+    } else if dx.abs() >= dy.abs() {
+        Some((dx.signum(), 0))
+    } else {
+        Some((0, dy.signum()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(cells: &[(i32, i32)]) -> Vec<Position> {
+        cells.iter().map(|&(x, y)| Position::new(x, y)).collect()
+    }
+
+    #[test]
+    fn test_direction() {
+        let origin = Position::new(3, 3);
+        assert_eq!(direction(origin, Position::new(5, 3)), Some((1, 0)));
+        assert_eq!(direction(origin, Position::new(2, 1)), Some((0, -1)));
+        // En diagonale, la direction est horizontale
+        assert_eq!(direction(origin, Position::new(1, 5)), Some((-1, 0)));
+        assert_eq!(direction(origin, origin), None);
+    }
+
+    #[test]
+    fn test_symmetric_shapes() {
+        let (caster, target) = (Position::new(0, 0), Position::new(5, 5));
+
+        assert_eq!(AreaShape::Single.cells(caster, target), vec![target]);
+        assert_eq!(
+            AreaShape::Cross { size: 1 }.cells(caster, target),
+            positions(&[(5, 5), (5, 4), (4, 5), (6, 5), (5, 6)])
+        );
+        let circle = AreaShape::Circle { size: 2 }.cells(caster, target);
+        assert_eq!(circle.len(), 13);
+        assert!(circle
+            .iter()
+            .all(|cell| cell.manhattan_distance(&target) <= 2));
+        assert_eq!(
+            AreaShape::Ring { size: 1 }.cells(caster, target),
+            positions(&[
+                (5, 4),
+                (4, 5),
+                (6, 5),
+                (5, 6),
+                (4, 4),
+                (6, 4),
+                (4, 6),
+                (6, 6),
+            ])
+        );
+        assert_eq!(AreaShape::Ring { size: 2 }.cells(caster, target).len(), 16);
+        assert_eq!(
+            AreaShape::Ring { size: 0 }.cells(caster, target),
+            vec![target]
+        );
+    }
+
+    #[test]
+    fn test_oriented_shapes() {
+        let caster = Position::new(2, 5);
+        let target = Position::new(4, 5);
+
+        assert_eq!(
+            AreaShape::Line { size: 2 }.cells(caster, target),
+            positions(&[(4, 5), (5, 5), (6, 5)])
+        );
+        assert_eq!(
+            AreaShape::Cone { size: 2 }.cells(caster, target),
+            positions(&[
+                (4, 5),
+                (5, 5),
+                (5, 4),
+                (6, 5),
+                (5, 6),
+                (6, 4),
+                (6, 6),
+                (6, 3),
+                (6, 7),
+            ])
+        );
+        // Vers le haut, la ligne remonte les rangées
+        assert_eq!(
+            AreaShape::Line { size: 1 }.cells(Position::new(4, 8), target),
+            positions(&[(4, 5), (4, 4)])
+        );
+        // Lancée sur soi, une zone orientée ne touche que le lanceur
+        assert_eq!(
+            AreaShape::Cone { size: 2 }.cells(target, target),
+            vec![target]
+        );
+    }
+}
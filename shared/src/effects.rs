@@ -0,0 +1,108 @@
+//! Effets temporaires (envoûtements) posés sur les joueurs
+//!
+//! Un effet est posé par un sort pour un nombre de tours de son lanceur : sa
+//! durée diminue au début de chaque tour du lanceur et l'effet expire à 0. Un
+//! effet d'un tour posé sur un adversaire dure donc jusqu'au prochain tour du
+//! lanceur, tours de la cible compris. Les effets actifs font partie de
+//! `PlayerState` : le client les affiche et les fait expirer lui-même en
+//! appliquant les événements de début de tour.
+
+use crate::protocol::{Element, PlayerId};
+use crate::spells::SpellId;
+use serde::{Deserialize, Serialize};
+
+/// Nature d'un effet temporaire
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusKind {
+    /// Dégâts élémentaires subis au début de chaque tour de la cible
+    Poison { element: Element, amount: u32 },
+    /// PA gagnés (ou perdus si négatif) à chaque tour
+    ActionPoints { amount: i32 },
+    /// PM gagnés (ou perdus si négatif) à chaque tour
+    MovementPoints { amount: i32 },
+    /// Bonus de dégâts en pourcentage, ajouté à toutes les caractéristiques élémentaires
+    DamageBoost { percent: i32 },
+    /// Résistance en pourcentage à un élément (négative pour une faiblesse)
+    Resistance { element: Element, percent: i32 },
+    /// Ne peut pas se déplacer
+    Rooted,
+    /// Ne subit aucun dégât
+    Invulnerable,
+    /// Absorbe les dégâts avant les points de vie, jusqu'à épuisement
+    Shield { amount: u32 },
+}
+
+/// Effet actif sur un joueur
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ActiveEffect {
+    pub kind: StatusKind,
+    /// Joueur ayant posé l'effet
+    pub caster_id: PlayerId,
+    /// Sort à l'origine de l'effet
+    pub spell_id: SpellId,
+    /// Tours du lanceur restant avant expiration
+    pub turns_remaining: u32,
+    /// Indique si l'effet peut être retiré par un désenvoûtement
+    pub dispellable: bool,
+}
+
+impl StatusKind {
+    /// Indique si l'effet nuit à sa cible ; il ne touche alors pas les alliés du lanceur
+    pub fn is_hostile(&self) -> bool {
+        match *self {
+            StatusKind::Poison { .. } | StatusKind::Rooted => true,
+            StatusKind::ActionPoints { amount } | StatusKind::MovementPoints { amount } => {
+                amount < 0
+            }
+            StatusKind::DamageBoost { percent } | StatusKind::Resistance { percent, .. } => {
+                percent < 0
+            }
+            StatusKind::Invulnerable | StatusKind::Shield { .. } => false,
+        }
+    }
+}
+
+impl std::fmt::Display for StatusKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatusKind::Poison { element, amount } => {
+                write!(f, "Poison ({} {:?} par tour)", amount, element)
+            }
+            StatusKind::ActionPoints { amount } => write!(f, "{:+} PA", amount),
+            StatusKind::MovementPoints { amount } => write!(f, "{:+} PM", amount),
+            StatusKind::DamageBoost { percent } => write!(f, "{:+} % de dégâts", percent),
+            StatusKind::Resistance { element, percent } => {
+                write!(f, "{:+} % de résistance {:?}", percent, element)
+            }
+            StatusKind::Rooted => write!(f, "Enraciné"),
+            StatusKind::Invulnerable => write!(f, "Invulnérable"),
+            StatusKind::Shield { amount } => write!(f, "Bouclier ({} PV)", amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_display() {
+        assert_eq!(StatusKind::ActionPoints { amount: 1 }.to_string(), "+1 PA");
+        assert_eq!(
+            StatusKind::MovementPoints { amount: -2 }.to_string(),
+            "-2 PM"
+        );
+        assert_eq!(
+            StatusKind::Shield { amount: 30 }.to_string(),
+            "Bouclier (30 PV)"
+        );
+    }
+
+    #[test]
+    fn test_hostile_statuses() {
+        assert!(StatusKind::Rooted.is_hostile());
+        assert!(StatusKind::MovementPoints { amount: -1 }.is_hostile());
+        assert!(!StatusKind::MovementPoints { amount: 1 }.is_hostile());
+        assert!(!StatusKind::Shield { amount: 10 }.is_hostile());
+    }
+}
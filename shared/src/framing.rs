@@ -0,0 +1,314 @@
+//! Découpage du flux réseau en trames préfixées par leur longueur
+//!
+//! Chaque trame est précédée de sa taille sur 4 octets (u32 little-endian).
+//! La taille annoncée est vérifiée avant toute allocation : un pair ne peut pas
+//! forcer la réservation d'un tampon plus grand que `max_frame_size`.
+
+use crate::protocol::serialization::{self, SerializationError, WireFormat};
+use crate::protocol::Message;
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Taille du préfixe de longueur d'une trame
+pub const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// Taille maximale par défaut du contenu d'une trame (1 Mio)
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 1024 * 1024;
+
+/// Erreur de découpage ou d'encodage d'une trame
+#[derive(Debug)]
+pub enum FrameError {
+    /// La trame annoncée ou à envoyer dépasse la taille maximale
+    FrameTooLarge { size: usize, max_frame_size: usize },
+    /// Le contenu de la trame n'est pas un message valide
+    Serialization(SerializationError),
+    /// Erreur d'entrée/sortie sur la connexion
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameError::FrameTooLarge {
+                size,
+                max_frame_size,
+            } => write!(
+                f,
+                "Trame trop grande ({} octets, maximum {})",
+                size, max_frame_size
+            ),
+            FrameError::Serialization(e) => write!(f, "Message invalide: {}", e),
+            FrameError::Io(e) => write!(f, "Erreur réseau: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+impl From<std::io::Error> for FrameError {
+    fn from(error: std::io::Error) -> Self {
+        FrameError::Io(error)
+    }
+}
+
+impl From<SerializationError> for FrameError {
+    fn from(error: SerializationError) -> Self {
+        FrameError::Serialization(error)
+    }
+}
+
+impl From<bincode::Error> for FrameError {
+    fn from(error: bincode::Error) -> Self {
+        FrameError::Serialization(error.into())
+    }
+}
+
+/// Codec de trames brutes : préfixe de longueur puis contenu
+#[derive(Debug, Clone, Copy)]
+pub struct FrameCodec {
+    max_frame_size: usize,
+}
+
+impl FrameCodec {
+    pub fn new(max_frame_size: usize) -> Self {
+        Self { max_frame_size }
+    }
+
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
+    fn check_size(&self, size: usize) -> Result<(), FrameError> {
+        if size > self.max_frame_size {
+            return Err(FrameError::FrameTooLarge {
+                size,
+                max_frame_size: self.max_frame_size,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for FrameCodec {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_FRAME_SIZE)
+    }
+}
+
+impl Decoder for FrameCodec {
+    type Item = BytesMut;
+    type Error = FrameError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>, FrameError> {
+        if src.len() < LENGTH_PREFIX_SIZE {
+            return Ok(None);
+        }
+
+        let mut prefix = [0u8; LENGTH_PREFIX_SIZE];
+        prefix.copy_from_slice(&src[..LENGTH_PREFIX_SIZE]);
+        let size = u32::from_le_bytes(prefix) as usize;
+        self.check_size(size)?;
+
+        let frame_len = LENGTH_PREFIX_SIZE + size;
+        if src.len() < frame_len {
+            // Trame incomplète : on attend la suite sans rien consommer
+            src.reserve(frame_len - src.len());
+            return Ok(None);
+        }
+
+        src.advance(LENGTH_PREFIX_SIZE);
+        Ok(Some(src.split_to(size)))
+    }
+}
+
+impl Encoder<&[u8]> for FrameCodec {
+    type Error = FrameError;
+
+    fn encode(&mut self, payload: &[u8], dst: &mut BytesMut) -> Result<(), FrameError> {
+        self.check_size(payload.len())?;
+
+        dst.reserve(LENGTH_PREFIX_SIZE + payload.len());
+        dst.put_u32_le(payload.len() as u32);
+        dst.extend_from_slice(payload);
+        Ok(())
+    }
+}
+
+impl Encoder<Vec<u8>> for FrameCodec {
+    type Error = FrameError;
+
+    fn encode(&mut self, payload: Vec<u8>, dst: &mut BytesMut) -> Result<(), FrameError> {
+        self.encode(payload.as_slice(), dst)
+    }
+}
+
+/// Codec de `Message` : une trame par message sérialisé
+///
+/// Le codec démarre en bincode, le format de la poignée de main ; `set_format`
+/// bascule sur le format négocié une fois le `Welcome` échangé.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessageCodec {
+    frames: FrameCodec,
+    format: WireFormat,
+}
+
+impl MessageCodec {
+    pub fn new(max_frame_size: usize) -> Self {
+        Self {
+            frames: FrameCodec::new(max_frame_size),
+            format: WireFormat::default(),
+        }
+    }
+
+    pub fn format(&self) -> WireFormat {
+        self.format
+    }
+
+    pub fn set_format(&mut self, format: WireFormat) {
+        self.format = format;
+    }
+}
+
+impl Decoder for MessageCodec {
+    type Item = Message;
+    type Error = FrameError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>, FrameError> {
+        match self.frames.decode(src)? {
+            Some(frame) => Ok(Some(serialization::deserialize_with(self.format, &frame)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Encoder<Message> for MessageCodec {
+    type Error = FrameError;
+
+    fn encode(&mut self, message: Message, dst: &mut BytesMut) -> Result<(), FrameError> {
+        let bytes = serialization::serialize_with(self.format, &message)?;
+        self.frames.encode(bytes.as_slice(), dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_message(codec: &mut MessageCodec, message: Message) -> BytesMut {
+        let mut buffer = BytesMut::new();
+        codec.encode(message, &mut buffer).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_message_roundtrip() {
+        let mut codec = MessageCodec::default();
+        let mut buffer = encode_message(&mut codec, Message::EndTurn { player_id: 7 });
+
+        let decoded = codec.decode(&mut buffer).unwrap();
+        assert!(matches!(decoded, Some(Message::EndTurn { player_id: 7 })));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_partial_reads() {
+        let mut codec = MessageCodec::default();
+        let encoded = encode_message(&mut codec, Message::EndTurn { player_id: 7 });
+
+        // Les octets arrivent un par un : rien n'est décodé avant le dernier
+        let mut buffer = BytesMut::new();
+        for (i, byte) in encoded.iter().enumerate() {
+            buffer.put_u8(*byte);
+            let decoded = codec.decode(&mut buffer).unwrap();
+            if i + 1 < encoded.len() {
+                assert!(decoded.is_none());
+            } else {
+                assert!(matches!(decoded, Some(Message::EndTurn { player_id: 7 })));
+            }
+        }
+    }
+
+    #[test]
+    fn test_several_frames_in_one_buffer() {
+        let mut codec = MessageCodec::default();
+        let mut buffer = encode_message(&mut codec, Message::EndTurn { player_id: 1 });
+        buffer.extend_from_slice(&encode_message(
+            &mut codec,
+            Message::EndTurn { player_id: 2 },
+        ));
+
+        assert!(matches!(
+            codec.decode(&mut buffer).unwrap(),
+            Some(Message::EndTurn { player_id: 1 })
+        ));
+        assert!(matches!(
+            codec.decode(&mut buffer).unwrap(),
+            Some(Message::EndTurn { player_id: 2 })
+        ));
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_oversized_length_prefix_is_rejected() {
+        let mut codec = FrameCodec::new(16);
+        let mut buffer = BytesMut::new();
+        buffer.put_u32_le(u32::MAX);
+
+        match codec.decode(&mut buffer) {
+            Err(FrameError::FrameTooLarge {
+                size,
+                max_frame_size,
+            }) => {
+                assert_eq!(size, u32::MAX as usize);
+                assert_eq!(max_frame_size, 16);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        // Rien n'a été réservé pour la trame annoncée
+        assert!(buffer.capacity() < 1024);
+    }
+
+    #[test]
+    fn test_oversized_payload_is_not_encoded() {
+        let mut codec = FrameCodec::new(4);
+        let mut buffer = BytesMut::new();
+
+        let result = codec.encode(&[0u8; 5][..], &mut buffer);
+        assert!(matches!(
+            result,
+            Err(FrameError::FrameTooLarge { size: 5, .. })
+        ));
+        assert!(buffer.is_empty());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_format_switch() {
+        let mut codec = MessageCodec::default();
+        codec.set_format(WireFormat::Json);
+        let mut buffer = encode_message(&mut codec, Message::EndTurn { player_id: 7 });
+
+        assert_eq!(
+            &buffer[LENGTH_PREFIX_SIZE..],
+            br#"{"EndTurn":{"player_id":7}}"#
+        );
+        assert!(matches!(
+            codec.decode(&mut buffer).unwrap(),
+            Some(Message::EndTurn { player_id: 7 })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_payload_is_a_serialization_error() {
+        let mut codec = MessageCodec::default();
+        let mut buffer = BytesMut::new();
+        FrameCodec::default()
+            .encode(&[0xff, 0xff, 0xff, 0xff][..], &mut buffer)
+            .unwrap();
+
+        assert!(matches!(
+            codec.decode(&mut buffer),
+            Err(FrameError::Serialization(_))
+        ));
+    }
+}
@@ -1,9 +1,39 @@
+pub mod area;
+pub mod effects;
+pub mod framing;
+pub mod line_of_sight;
+pub mod map;
+pub mod pathfinding;
+pub mod spells;
+pub mod summons;
+
 pub mod protocol {
+    use crate::effects::{ActiveEffect, StatusKind};
+    use crate::map::GameMap;
+    use crate::spells::{self, KnownSpell, Spell, SpellId};
+    use crate::summons::{self, Creature, CreatureId, Summon};
     use serde::{Deserialize, Serialize};
 
     /// Identifiant unique d'un joueur
     pub type PlayerId = u32;
 
+    /// Identifiant choisi par le client pour corréler une requête et sa réponse
+    pub type RequestId = u32;
+
+    /// Numéro de séquence des états du monde envoyés à un client
+    pub type Sequence = u64;
+
+    /// Version du protocole réseau, à incrémenter à chaque changement de `Message`
+    pub const PROTOCOL_VERSION: u32 = 20;
+
+    /// Versions du protocole acceptées par ce build
+    pub const SUPPORTED_PROTOCOL_VERSIONS: &[u32] = &[PROTOCOL_VERSION];
+
+    /// Indique si une version du protocole est acceptée par ce build
+    pub fn is_protocol_version_supported(version: u32) -> bool {
+        SUPPORTED_PROTOCOL_VERSIONS.contains(&version)
+    }
+
     /// Position sur la grille (x, y)
     #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct Position {
@@ -22,56 +52,369 @@ pub mod protocol {
         }
     }
 
+    /// Points de vie d'un personnage sans vitalité
+    pub const BASE_HEALTH: u32 = 100;
+
+    /// PA d'un personnage au début de son tour
+    pub const BASE_ACTION_POINTS: u32 = 6;
+
+    /// PM d'un personnage au début de son tour
+    pub const BASE_MOVEMENT_POINTS: u32 = 3;
+
+    /// Équipe d'un combattant ; les deux équipes jouent en alternance
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub enum Team {
+        #[default]
+        Red,
+        Blue,
+    }
+
+    impl Team {
+        /// Équipe adverse
+        pub fn opponent(self) -> Team {
+            match self {
+                Team::Red => Team::Blue,
+                Team::Blue => Team::Red,
+            }
+        }
+    }
+
+    impl std::fmt::Display for Team {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Team::Red => write!(f, "rouge"),
+                Team::Blue => write!(f, "bleue"),
+            }
+        }
+    }
+
+    /// Élément des dégâts d'un sort
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Element {
+        /// Terre, augmenté par la force
+        Earth,
+        /// Feu, augmenté par l'intelligence
+        Fire,
+        /// Eau, augmenté par la chance
+        Water,
+        /// Air, augmenté par l'agilité
+        Air,
+    }
+
+    /// Caractéristiques d'un personnage
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct Stats {
+        pub strength: i32,
+        pub intelligence: i32,
+        pub chance: i32,
+        pub agility: i32,
+        pub vitality: i32,
+        pub wisdom: i32,
+    }
+
+    impl Stats {
+        /// Caractéristique qui augmente les dégâts d'un élément
+        pub fn for_element(&self, element: Element) -> i32 {
+            match element {
+                Element::Earth => self.strength,
+                Element::Fire => self.intelligence,
+                Element::Water => self.chance,
+                Element::Air => self.agility,
+            }
+        }
+
+        /// Fuite : aide à quitter une case au contact d'ennemis (1 point pour 10 d'agilité)
+        pub fn dodge(&self) -> i32 {
+            self.agility / 10
+        }
+
+        /// Tacle : retient les ennemis qui quittent une case adjacente (1 point pour 10 d'agilité)
+        pub fn tackle(&self) -> i32 {
+            self.agility / 10
+        }
+    }
+
+    /// Résistance à un élément
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct Resistance {
+        /// Pourcentage de dégâts ignorés, négatif pour une faiblesse
+        pub percent: i32,
+        /// Dégâts retirés après le pourcentage
+        pub flat: i32,
+    }
+
+    /// Résistances d'un personnage
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct Resistances {
+        pub earth: Resistance,
+        pub fire: Resistance,
+        pub water: Resistance,
+        pub air: Resistance,
+        /// Réduction appliquée en dernier à tous les dégâts, quel que soit l'élément
+        pub damage_reduction: i32,
+    }
+
+    impl Resistances {
+        /// Résistance à un élément
+        pub fn for_element(&self, element: Element) -> Resistance {
+            match element {
+                Element::Earth => self.earth,
+                Element::Fire => self.fire,
+                Element::Water => self.water,
+                Element::Air => self.air,
+            }
+        }
+
+        /// Résistance modifiable à un élément
+        pub fn for_element_mut(&mut self, element: Element) -> &mut Resistance {
+            match element {
+                Element::Earth => &mut self.earth,
+                Element::Fire => &mut self.fire,
+                Element::Water => &mut self.water,
+                Element::Air => &mut self.air,
+            }
+        }
+    }
+
+    /// Phase du combat
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum FightPhase {
+        /// Les joueurs choisissent leur cellule de départ parmi celles de leur équipe
+        #[default]
+        Placement,
+        /// Les combattants jouent chacun leur tour
+        Fighting,
+        /// Une équipe a été éliminée
+        Ended,
+    }
+
     /// État d'un joueur dans le jeu
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
     pub struct PlayerState {
         pub id: PlayerId,
+        pub team: Team,
         pub position: Position,
         pub action_points: u32,   // PA (Points d'Action)
         pub movement_points: u32, // PM (Points de Mouvement)
         pub health: u32,
         pub max_health: u32,
         pub is_alive: bool,
+        /// Sorts connus et leur état (relance, lancers du tour)
+        pub spells: Vec<KnownSpell>,
+        pub stats: Stats,
+        pub resistances: Resistances,
+        /// Effets temporaires actifs, dans l'ordre où ils ont été posés
+        pub effects: Vec<ActiveEffect>,
+        /// Indique si le joueur a validé son placement
+        pub ready: bool,
+        /// Indique si le joueur a laissé passer plusieurs tours de suite sans jouer
+        pub afk: bool,
+        /// Invocateur et créature, si le combattant est une invocation ; fixé à
+        /// la création, il ne figure pas dans les deltas
+        pub summon: Option<Summon>,
     }
 
     impl PlayerState {
         pub fn new(id: PlayerId, position: Position) -> Self {
+            Self::with_stats(id, position, Stats::default())
+        }
+
+        /// Crée un joueur à partir des caractéristiques de son personnage
+        ///
+        /// Chaque point de vitalité ajoute un point de vie au maximum.
+        pub fn with_stats(id: PlayerId, position: Position, stats: Stats) -> Self {
+            let max_health = (BASE_HEALTH as i32 + stats.vitality).max(1) as u32;
             Self {
                 id,
+                team: Team::default(),
                 position,
-                action_points: 6,   // PA par défaut
-                movement_points: 3, // PM par défaut
-                health: 100,
-                max_health: 100,
+                action_points: BASE_ACTION_POINTS,
+                movement_points: BASE_MOVEMENT_POINTS,
+                health: max_health,
+                max_health,
                 is_alive: true,
+                spells: spells::STARTING_SPELLS
+                    .iter()
+                    .map(|&spell_id| KnownSpell::new(spell_id))
+                    .collect(),
+                stats,
+                resistances: Resistances::default(),
+                effects: Vec::new(),
+                ready: false,
+                afk: false,
+                summon: None,
             }
         }
 
-        /// Réinitialise les PA et PM au début d'un tour
+        /// Crée l'invocation d'une créature, dans l'équipe de son invocateur
+        pub fn summoned(
+            id: PlayerId,
+            position: Position,
+            team: Team,
+            summoner_id: PlayerId,
+            creature: &Creature,
+        ) -> Self {
+            let mut summon = Self::with_stats(id, position, creature.stats);
+            summon.team = team;
+            summon.ready = true;
+            summon.spells = creature
+                .spells
+                .iter()
+                .map(|&spell_id| KnownSpell::new(spell_id))
+                .collect();
+            summon.summon = Some(Summon {
+                summoner_id,
+                creature_id: creature.id,
+            });
+            summon
+        }
+
+        /// Invocateur du combattant, `None` si ce n'est pas une invocation
+        pub fn summoner(&self) -> Option<PlayerId> {
+            self.summon.map(|summon| summon.summoner_id)
+        }
+
+        /// Réinitialise les PA, les PM et les sorts au début d'un tour
+        ///
+        /// Les effets de PA/PM actifs s'ajoutent aux valeurs de base.
         pub fn reset_turn(&mut self) {
-            self.action_points = 6;
-            self.movement_points = 3;
+            let (mut action_points, mut movement_points) =
+                (BASE_ACTION_POINTS as i32, BASE_MOVEMENT_POINTS as i32);
+            for effect in &self.effects {
+                match effect.kind {
+                    StatusKind::ActionPoints { amount } => action_points += amount,
+                    StatusKind::MovementPoints { amount } => movement_points += amount,
+                    _ => {}
+                }
+            }
+            self.action_points = action_points.max(0) as u32;
+            self.movement_points = movement_points.max(0) as u32;
+            for known in &mut self.spells {
+                known.start_turn();
+            }
+        }
+
+        /// Initiative du joueur, qui fixe sa place dans la frise du combat
+        ///
+        /// Somme des caractéristiques élémentaires (force, intelligence, chance,
+        /// agilité), proportionnelle aux points de vie restants.
+        pub fn initiative(&self) -> i32 {
+            let characteristics = (self.stats.strength
+                + self.stats.intelligence
+                + self.stats.chance
+                + self.stats.agility)
+                .max(0) as i64;
+            (characteristics * self.health as i64 / self.max_health.max(1) as i64) as i32
+        }
+
+        /// Indique si un effet actif vérifie `predicate`
+        pub fn has_status(&self, predicate: impl Fn(&StatusKind) -> bool) -> bool {
+            self.effects.iter().any(|effect| predicate(&effect.kind))
+        }
+
+        /// Indique si le joueur est enraciné
+        pub fn is_rooted(&self) -> bool {
+            self.has_status(|kind| *kind == StatusKind::Rooted)
+        }
+
+        /// Indique si le joueur est invulnérable
+        pub fn is_invulnerable(&self) -> bool {
+            self.has_status(|kind| *kind == StatusKind::Invulnerable)
+        }
+
+        /// Caractéristiques modifiées par les bonus de dégâts actifs
+        pub fn effective_stats(&self) -> Stats {
+            let mut stats = self.stats;
+            for effect in &self.effects {
+                if let StatusKind::DamageBoost { percent } = effect.kind {
+                    stats.strength += percent;
+                    stats.intelligence += percent;
+                    stats.chance += percent;
+                    stats.agility += percent;
+                }
+            }
+            stats
+        }
+
+        /// Résistances modifiées par les effets de résistance actifs
+        pub fn effective_resistances(&self) -> Resistances {
+            let mut resistances = self.resistances;
+            for effect in &self.effects {
+                if let StatusKind::Resistance { element, percent } = effect.kind {
+                    resistances.for_element_mut(element).percent += percent;
+                }
+            }
+            resistances
+        }
+
+        /// Absorbe des dégâts avec les boucliers actifs, du plus ancien au plus récent
+        ///
+        /// Les boucliers épuisés sont retirés. Retourne les dégâts absorbés.
+        pub fn absorb_damage(&mut self, amount: u32) -> u32 {
+            let mut absorbed = 0;
+            for effect in &mut self.effects {
+                if let StatusKind::Shield { amount: shield } = &mut effect.kind {
+                    let taken = (*shield).min(amount - absorbed);
+                    *shield -= taken;
+                    absorbed += taken;
+                }
+            }
+            self.effects
+                .retain(|effect| effect.kind != StatusKind::Shield { amount: 0 });
+            absorbed
+        }
+
+        /// Retire les effets désenvoûtables, et indique si au moins un a été retiré
+        pub fn dispel(&mut self) -> bool {
+            let before = self.effects.len();
+            self.effects.retain(|effect| !effect.dispellable);
+            self.effects.len() != before
+        }
+
+        /// Trouve un sort connu par son ID
+        pub fn known_spell(&self, spell_id: SpellId) -> Option<&KnownSpell> {
+            self.spells.iter().find(|known| known.spell_id == spell_id)
+        }
+
+        /// Enregistre le lancer d'un sort connu
+        pub fn record_cast(&mut self, spell: &Spell) {
+            if let Some(known) = self
+                .spells
+                .iter_mut()
+                .find(|known| known.spell_id == spell.id)
+            {
+                known.record_cast(spell);
+            }
         }
     }
 
     /// État du monde de jeu
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
     pub struct WorldState {
         pub players: Vec<PlayerState>,
+        /// Ordre de jeu des combattants ; les morts y restent mais passent leur tour
+        pub timeline: Vec<PlayerId>,
         pub current_turn: PlayerId,
+        /// Numéro du tour de jeu, incrémenté quand toute la frise a joué
         pub turn_number: u32,
         pub map_width: i32,
         pub map_height: i32,
+        pub phase: FightPhase,
+        /// Secondes restantes avant la fin automatique du tour en cours
+        pub turn_time_remaining: u32,
     }
 
     impl WorldState {
         pub fn new(map_width: i32, map_height: i32) -> Self {
             Self {
                 players: Vec::new(),
+                timeline: Vec::new(),
                 current_turn: 0,
                 turn_number: 1,
                 map_width,
                 map_height,
+                phase: FightPhase::default(),
+                turn_time_remaining: 0,
             }
         }
 
@@ -84,22 +427,976 @@ pub mod protocol {
         pub fn get_player_mut(&mut self, player_id: PlayerId) -> Option<&mut PlayerState> {
             self.players.iter_mut().find(|p| p.id == player_id)
         }
+
+        /// Décompte un tour des effets posés par un joueur, au début de son tour
+        ///
+        /// Les effets arrivés à 0 tour expirent.
+        pub fn expire_effects(&mut self, caster_id: PlayerId) {
+            for player in &mut self.players {
+                for effect in &mut player.effects {
+                    if effect.caster_id == caster_id {
+                        effect.turns_remaining = effect.turns_remaining.saturating_sub(1);
+                    }
+                }
+                player.effects.retain(|effect| effect.turns_remaining > 0);
+            }
+        }
+
+        /// Ajoute une invocation au combat
+        ///
+        /// Elle prend place dans la frise juste après son invocateur et les
+        /// invocations qu'il a déjà appelées.
+        pub fn add_summon(&mut self, summon: PlayerState) {
+            let summoner_id = summon.summoner();
+            if let Some(index) = self.timeline.iter().position(|&id| Some(id) == summoner_id) {
+                let mut insert_at = index + 1;
+                while self.timeline.get(insert_at).is_some_and(|&id| {
+                    self.get_player(id).and_then(PlayerState::summoner) == summoner_id
+                }) {
+                    insert_at += 1;
+                }
+                self.timeline.insert(insert_at, summon.id);
+            }
+            self.players.push(summon);
+        }
+
+        /// Retire tous les effets posés par un joueur, à sa mort
+        pub fn remove_effects_from(&mut self, caster_id: PlayerId) {
+            for player in &mut self.players {
+                player
+                    .effects
+                    .retain(|effect| effect.caster_id != caster_id);
+            }
+        }
+
+        /// Applique un delta à l'état du monde
+        ///
+        /// L'appelant doit vérifier que l'état courant correspond à `delta.base_sequence`.
+        pub fn apply_delta(&mut self, delta: &WorldDelta) {
+            if let Some(current_turn) = delta.current_turn {
+                self.current_turn = current_turn;
+            }
+            if let Some(turn_number) = delta.turn_number {
+                self.turn_number = turn_number;
+            }
+            if let Some(timeline) = &delta.timeline {
+                self.timeline = timeline.clone();
+            }
+            if let Some(phase) = delta.phase {
+                self.phase = phase;
+            }
+            if let Some(turn_time_remaining) = delta.turn_time_remaining {
+                self.turn_time_remaining = turn_time_remaining;
+            }
+
+            self.players
+                .retain(|p| !delta.removed_players.contains(&p.id));
+
+            for player_delta in &delta.updated_players {
+                if let Some(player) = self.get_player_mut(player_delta.id) {
+                    player_delta.apply(player);
+                }
+            }
+
+            for added in &delta.added_players {
+                match self.get_player_mut(added.id) {
+                    Some(player) => *player = added.clone(),
+                    None => self.players.push(added.clone()),
+                }
+            }
+        }
+
+        /// Applique un événement de combat à l'état du monde
+        pub fn apply_event(&mut self, event: &GameEvent) {
+            match event {
+                GameEvent::Moved {
+                    player_id, path, ..
+                } => {
+                    if let (Some(player), Some(destination)) =
+                        (self.get_player_mut(*player_id), path.last())
+                    {
+                        player.position = *destination;
+                    }
+                }
+                GameEvent::Damaged {
+                    target_id, amount, ..
+                } => {
+                    if let Some(target) = self.get_player_mut(*target_id) {
+                        target.health = target.health.saturating_sub(*amount);
+                    }
+                }
+                GameEvent::Died { player_id } => {
+                    if let Some(player) = self.get_player_mut(*player_id) {
+                        player.health = 0;
+                        player.is_alive = false;
+                    }
+                    self.remove_effects_from(*player_id);
+                }
+                GameEvent::TurnStarted {
+                    player_id,
+                    turn_number,
+                    time_limit,
+                } => {
+                    self.current_turn = *player_id;
+                    self.turn_number = *turn_number;
+                    self.turn_time_remaining = *time_limit;
+                    self.expire_effects(*player_id);
+                    if let Some(player) = self.get_player_mut(*player_id) {
+                        player.reset_turn();
+                    }
+                }
+                GameEvent::SpellCast {
+                    caster_id,
+                    spell_id,
+                    ..
+                } => {
+                    if let (Some(caster), Some(spell)) =
+                        (self.get_player_mut(*caster_id), spells::get(*spell_id))
+                    {
+                        caster.record_cast(spell);
+                    }
+                }
+                GameEvent::Healed {
+                    target_id, amount, ..
+                } => {
+                    if let Some(target) = self.get_player_mut(*target_id) {
+                        target.health = (target.health + amount).min(target.max_health);
+                    }
+                }
+                GameEvent::CriticalHit { .. } => {}
+                GameEvent::ApMpRemoved {
+                    target_id,
+                    action_points,
+                    movement_points,
+                    ..
+                } => {
+                    if let Some(target) = self.get_player_mut(*target_id) {
+                        target.action_points = target.action_points.saturating_sub(*action_points);
+                        target.movement_points =
+                            target.movement_points.saturating_sub(*movement_points);
+                    }
+                }
+                GameEvent::StatusApplied { target_id, effect } => {
+                    if let Some(target) = self.get_player_mut(*target_id) {
+                        target.effects.push(effect.clone());
+                    }
+                }
+                GameEvent::ShieldAbsorbed { target_id, amount } => {
+                    if let Some(target) = self.get_player_mut(*target_id) {
+                        target.absorb_damage(*amount);
+                    }
+                }
+                GameEvent::StatusDispelled { target_id, .. } => {
+                    if let Some(target) = self.get_player_mut(*target_id) {
+                        target.dispel();
+                    }
+                }
+                GameEvent::TurnEnded { .. } => {}
+                GameEvent::Placed {
+                    player_id,
+                    position,
+                } => {
+                    if let Some(player) = self.get_player_mut(*player_id) {
+                        player.position = *position;
+                    }
+                }
+                GameEvent::ReadyChanged { player_id, ready } => {
+                    if let Some(player) = self.get_player_mut(*player_id) {
+                        player.ready = *ready;
+                    }
+                }
+                GameEvent::TurnTimedOut { .. } => {}
+                GameEvent::AfkChanged { player_id, afk } => {
+                    if let Some(player) = self.get_player_mut(*player_id) {
+                        player.afk = *afk;
+                    }
+                }
+                GameEvent::FightStarted { timeline } => {
+                    self.timeline = timeline.clone();
+                    self.phase = FightPhase::Fighting;
+                }
+                GameEvent::Pushed { player_id, path } => {
+                    if let (Some(player), Some(destination)) =
+                        (self.get_player_mut(*player_id), path.last())
+                    {
+                        player.position = *destination;
+                    }
+                }
+                GameEvent::Swapped {
+                    first_id,
+                    second_id,
+                } => {
+                    let first = self.get_player(*first_id).map(|p| p.position);
+                    let second = self.get_player(*second_id).map(|p| p.position);
+                    if let (Some(first), Some(second)) = (first, second) {
+                        if let Some(player) = self.get_player_mut(*first_id) {
+                            player.position = second;
+                        }
+                        if let Some(player) = self.get_player_mut(*second_id) {
+                            player.position = first;
+                        }
+                    }
+                }
+                GameEvent::Teleported {
+                    player_id,
+                    position,
+                } => {
+                    if let Some(player) = self.get_player_mut(*player_id) {
+                        player.position = *position;
+                    }
+                }
+                GameEvent::Summoned {
+                    summoner_id,
+                    summon_id,
+                    creature_id,
+                    position,
+                } => {
+                    let team = self.get_player(*summoner_id).map(|p| p.team);
+                    if let (Some(team), Some(creature), None) = (
+                        team,
+                        summons::get(*creature_id),
+                        self.get_player(*summon_id),
+                    ) {
+                        self.add_summon(PlayerState::summoned(
+                            *summon_id,
+                            *position,
+                            team,
+                            *summoner_id,
+                            creature,
+                        ));
+                    }
+                }
+                GameEvent::ApMpChanged {
+                    player_id,
+                    action_points,
+                    movement_points,
+                } => {
+                    if let Some(player) = self.get_player_mut(*player_id) {
+                        player.action_points = *action_points;
+                        player.movement_points = *movement_points;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Champs modifiés d'un joueur entre deux états du monde
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+    pub struct PlayerDelta {
+        pub id: PlayerId,
+        pub team: Option<Team>,
+        pub position: Option<Position>,
+        pub action_points: Option<u32>,
+        pub movement_points: Option<u32>,
+        pub health: Option<u32>,
+        pub max_health: Option<u32>,
+        pub is_alive: Option<bool>,
+        pub spells: Option<Vec<KnownSpell>>,
+        pub stats: Option<Stats>,
+        pub resistances: Option<Resistances>,
+        pub effects: Option<Vec<ActiveEffect>>,
+        pub ready: Option<bool>,
+        pub afk: Option<bool>,
+    }
+
+    /// Retourne la nouvelle valeur d'un champ seulement si elle a changé
+    fn changed<T: PartialEq + Clone>(old: &T, new: &T) -> Option<T> {
+        (old != new).then(|| new.clone())
+    }
+
+    impl PlayerDelta {
+        /// Calcule les champs modifiés entre deux états d'un même joueur
+        pub fn between(old: &PlayerState, new: &PlayerState) -> Self {
+            Self {
+                id: new.id,
+                team: changed(&old.team, &new.team),
+                position: changed(&old.position, &new.position),
+                action_points: changed(&old.action_points, &new.action_points),
+                movement_points: changed(&old.movement_points, &new.movement_points),
+                health: changed(&old.health, &new.health),
+                max_health: changed(&old.max_health, &new.max_health),
+                is_alive: changed(&old.is_alive, &new.is_alive),
+                spells: changed(&old.spells, &new.spells),
+                stats: changed(&old.stats, &new.stats),
+                resistances: changed(&old.resistances, &new.resistances),
+                effects: changed(&old.effects, &new.effects),
+                ready: changed(&old.ready, &new.ready),
+                afk: changed(&old.afk, &new.afk),
+            }
+        }
+
+        /// Indique si aucun champ n'a changé
+        pub fn is_empty(&self) -> bool {
+            *self
+                == Self {
+                    id: self.id,
+                    ..Default::default()
+                }
+        }
+
+        /// Applique les champs modifiés à un joueur
+        pub fn apply(&self, player: &mut PlayerState) {
+            if let Some(team) = self.team {
+                player.team = team;
+            }
+            if let Some(position) = self.position {
+                player.position = position;
+            }
+            if let Some(action_points) = self.action_points {
+                player.action_points = action_points;
+            }
+            if let Some(movement_points) = self.movement_points {
+                player.movement_points = movement_points;
+            }
+            if let Some(health) = self.health {
+                player.health = health;
+            }
+            if let Some(max_health) = self.max_health {
+                player.max_health = max_health;
+            }
+            if let Some(is_alive) = self.is_alive {
+                player.is_alive = is_alive;
+            }
+            if let Some(spells) = &self.spells {
+                player.spells = spells.clone();
+            }
+            if let Some(stats) = self.stats {
+                player.stats = stats;
+            }
+            if let Some(resistances) = self.resistances {
+                player.resistances = resistances;
+            }
+            if let Some(effects) = &self.effects {
+                player.effects = effects.clone();
+            }
+            if let Some(ready) = self.ready {
+                player.ready = ready;
+            }
+            if let Some(afk) = self.afk {
+                player.afk = afk;
+            }
+        }
+    }
+
+    /// Différence entre deux états du monde envoyés à un même client
+    ///
+    /// Ne s'applique que sur l'état de séquence `base_sequence` ; un client qui
+    /// détecte un trou dans la séquence demande un `Resync`.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct WorldDelta {
+        pub base_sequence: Sequence,
+        pub sequence: Sequence,
+        pub current_turn: Option<PlayerId>,
+        pub turn_number: Option<u32>,
+        pub timeline: Option<Vec<PlayerId>>,
+        pub phase: Option<FightPhase>,
+        pub turn_time_remaining: Option<u32>,
+        pub added_players: Vec<PlayerState>,
+        pub removed_players: Vec<PlayerId>,
+        pub updated_players: Vec<PlayerDelta>,
+    }
+
+    impl WorldDelta {
+        /// Calcule le delta permettant de passer de `old` à `new`
+        pub fn between(
+            old: &WorldState,
+            new: &WorldState,
+            base_sequence: Sequence,
+            sequence: Sequence,
+        ) -> Self {
+            let mut added_players = Vec::new();
+            let mut updated_players = Vec::new();
+            for player in &new.players {
+                match old.get_player(player.id) {
+                    Some(previous) => {
+                        let player_delta = PlayerDelta::between(previous, player);
+                        if !player_delta.is_empty() {
+                            updated_players.push(player_delta);
+                        }
+                    }
+                    None => added_players.push(player.clone()),
+                }
+            }
+
+            let removed_players = old
+                .players
+                .iter()
+                .filter(|p| new.get_player(p.id).is_none())
+                .map(|p| p.id)
+                .collect();
+
+            Self {
+                base_sequence,
+                sequence,
+                current_turn: changed(&old.current_turn, &new.current_turn),
+                turn_number: changed(&old.turn_number, &new.turn_number),
+                timeline: changed(&old.timeline, &new.timeline),
+                phase: changed(&old.phase, &new.phase),
+                turn_time_remaining: changed(&old.turn_time_remaining, &new.turn_time_remaining),
+                added_players,
+                removed_players,
+                updated_players,
+            }
+        }
+
+        /// Indique si le delta ne contient aucun changement
+        pub fn is_empty(&self) -> bool {
+            self.current_turn.is_none()
+                && self.turn_number.is_none()
+                && self.timeline.is_none()
+                && self.phase.is_none()
+                && self.turn_time_remaining.is_none()
+                && self.added_players.is_empty()
+                && self.removed_players.is_empty()
+                && self.updated_players.is_empty()
+        }
+    }
+
+    /// Points perdus par un joueur taclé pendant un déplacement
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct TackleLoss {
+        pub movement_points: u32,
+        pub action_points: u32,
+    }
+
+    /// Événement de combat produit par le serveur à chaque modification de l'état
+    ///
+    /// Les événements alimentent les animations, le journal de combat et les replays ;
+    /// appliqués dans l'ordre avec `WorldState::apply_event`, ils reproduisent l'état du serveur.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub enum GameEvent {
+        /// Un joueur s'est déplacé case par case (position de départ incluse)
+        ///
+        /// `tackle` indique les points perdus en quittant des cases au contact
+        /// d'ennemis ; le chemin s'arrête là où le joueur n'a plus de PM.
+        Moved {
+            player_id: PlayerId,
+            path: Vec<Position>,
+            tackle: Option<TackleLoss>,
+        },
+        /// Un joueur a subi des dégâts
+        Damaged {
+            target_id: PlayerId,
+            source_id: PlayerId,
+            amount: u32,
+        },
+        /// Un joueur est mort
+        Died { player_id: PlayerId },
+        /// Début du tour d'un joueur, qui dispose de `time_limit` secondes pour jouer
+        TurnStarted {
+            player_id: PlayerId,
+            turn_number: u32,
+            time_limit: u32,
+        },
+        /// Fin du tour d'un joueur
+        TurnEnded { player_id: PlayerId },
+        /// Nouvelles valeurs de PA/PM d'un joueur
+        ApMpChanged {
+            player_id: PlayerId,
+            action_points: u32,
+            movement_points: u32,
+        },
+        /// Un joueur a lancé un sort ; ses effets suivent dans d'autres événements
+        SpellCast {
+            caster_id: PlayerId,
+            spell_id: SpellId,
+            target_cell: Position,
+        },
+        /// Un joueur a été soigné
+        Healed {
+            target_id: PlayerId,
+            source_id: PlayerId,
+            amount: u32,
+        },
+        /// Le dernier sort lancé est un coup critique
+        CriticalHit {
+            caster_id: PlayerId,
+            spell_id: SpellId,
+        },
+        /// Un joueur a perdu des PA ou des PM ; `dodged` points ont été esquivés
+        ApMpRemoved {
+            target_id: PlayerId,
+            source_id: PlayerId,
+            action_points: u32,
+            movement_points: u32,
+            dodged: u32,
+        },
+        /// Un effet temporaire a été posé sur un joueur
+        StatusApplied {
+            target_id: PlayerId,
+            effect: ActiveEffect,
+        },
+        /// Les boucliers d'un joueur ont absorbé des dégâts
+        ShieldAbsorbed { target_id: PlayerId, amount: u32 },
+        /// Les effets désenvoûtables d'un joueur ont été retirés
+        StatusDispelled {
+            target_id: PlayerId,
+            source_id: PlayerId,
+        },
+        /// Un joueur a changé de cellule de départ pendant le placement
+        Placed {
+            player_id: PlayerId,
+            position: Position,
+        },
+        /// Un joueur a validé ou annulé son placement
+        ReadyChanged { player_id: PlayerId, ready: bool },
+        /// Fin du placement : le combat commence dans l'ordre de la frise
+        FightStarted { timeline: Vec<PlayerId> },
+        /// Le temps du tour est écoulé ; le tour se termine automatiquement
+        TurnTimedOut { player_id: PlayerId },
+        /// Un joueur est signalé absent, ou ne l'est plus
+        AfkChanged { player_id: PlayerId, afk: bool },
+        /// Un joueur a été poussé ou attiré case par case (position de départ incluse)
+        Pushed {
+            player_id: PlayerId,
+            path: Vec<Position>,
+        },
+        /// Deux joueurs ont échangé leurs positions
+        Swapped {
+            first_id: PlayerId,
+            second_id: PlayerId,
+        },
+        /// Un joueur s'est téléporté
+        Teleported {
+            player_id: PlayerId,
+            position: Position,
+        },
+        /// Un joueur a invoqué une créature, qui rejoint la frise après lui
+        Summoned {
+            summoner_id: PlayerId,
+            summon_id: PlayerId,
+            creature_id: CreatureId,
+            position: Position,
+        },
+    }
+
+    impl std::fmt::Display for GameEvent {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                GameEvent::Moved {
+                    player_id,
+                    path,
+                    tackle,
+                } => {
+                    match path.last() {
+                        Some(destination) => write!(
+                            f,
+                            "Joueur {} se déplace en ({}, {})",
+                            player_id, destination.x, destination.y
+                        )?,
+                        None => write!(f, "Joueur {} reste sur place", player_id)?,
+                    }
+                    match tackle {
+                        Some(tackle) => write!(
+                            f,
+                            ", taclé (-{} PM, -{} PA)",
+                            tackle.movement_points, tackle.action_points
+                        ),
+                        None => Ok(()),
+                    }
+                }
+                GameEvent::Damaged {
+                    target_id,
+                    source_id,
+                    amount,
+                } => write!(
+                    f,
+                    "Joueur {} subit {} dégâts de la part du joueur {}",
+                    target_id, amount, source_id
+                ),
+                GameEvent::Died { player_id } => write!(f, "Joueur {} est mort", player_id),
+                GameEvent::TurnStarted {
+                    player_id,
+                    turn_number,
+                    ..
+                } => write!(f, "Tour {} : au joueur {} de jouer", turn_number, player_id),
+                GameEvent::TurnEnded { player_id } => {
+                    write!(f, "Joueur {} termine son tour", player_id)
+                }
+                GameEvent::ApMpChanged {
+                    player_id,
+                    action_points,
+                    movement_points,
+                } => write!(
+                    f,
+                    "Joueur {} : {} PA, {} PM",
+                    player_id, action_points, movement_points
+                ),
+                GameEvent::SpellCast {
+                    caster_id,
+                    spell_id,
+                    target_cell,
+                } => {
+                    let name = spells::get(*spell_id).map_or("sort inconnu", |spell| spell.name);
+                    write!(
+                        f,
+                        "Joueur {} lance {} en ({}, {})",
+                        caster_id, name, target_cell.x, target_cell.y
+                    )
+                }
+                GameEvent::Healed {
+                    target_id,
+                    source_id,
+                    amount,
+                } => write!(
+                    f,
+                    "Joueur {} récupère {} PV grâce au joueur {}",
+                    target_id, amount, source_id
+                ),
+                GameEvent::CriticalHit { caster_id, .. } => {
+                    write!(f, "Coup critique du joueur {} !", caster_id)
+                }
+                GameEvent::ApMpRemoved {
+                    target_id,
+                    action_points,
+                    movement_points,
+                    dodged,
+                    ..
+                } => write!(
+                    f,
+                    "Joueur {} perd {} PA et {} PM ({} esquivés)",
+                    target_id, action_points, movement_points, dodged
+                ),
+                GameEvent::StatusApplied { target_id, effect } => write!(
+                    f,
+                    "Joueur {} : {} pendant {} tours",
+                    target_id, effect.kind, effect.turns_remaining
+                ),
+                GameEvent::ShieldAbsorbed { target_id, amount } => write!(
+                    f,
+                    "Le bouclier du joueur {} absorbe {} dégâts",
+                    target_id, amount
+                ),
+                GameEvent::StatusDispelled {
+                    target_id,
+                    source_id,
+                } => write!(
+                    f,
+                    "Joueur {} est désenvoûté par le joueur {}",
+                    target_id, source_id
+                ),
+                GameEvent::Placed {
+                    player_id,
+                    position,
+                } => write!(
+                    f,
+                    "Joueur {} se place en ({}, {})",
+                    player_id, position.x, position.y
+                ),
+                GameEvent::ReadyChanged { player_id, ready } => {
+                    if *ready {
+                        write!(f, "Joueur {} est prêt", player_id)
+                    } else {
+                        write!(f, "Joueur {} n'est plus prêt", player_id)
+                    }
+                }
+                GameEvent::FightStarted { .. } => write!(f, "Le combat commence !"),
+                GameEvent::TurnTimedOut { player_id } => {
+                    write!(f, "Temps écoulé pour le joueur {}", player_id)
+                }
+                GameEvent::AfkChanged { player_id, afk } => {
+                    if *afk {
+                        write!(f, "Joueur {} est absent", player_id)
+                    } else {
+                        write!(f, "Joueur {} est de retour", player_id)
+                    }
+                }
+                GameEvent::Pushed { player_id, path } => match path.last() {
+                    Some(destination) => write!(
+                        f,
+                        "Joueur {} est déplacé en ({}, {})",
+                        player_id, destination.x, destination.y
+                    ),
+                    None => write!(f, "Joueur {} ne bouge pas", player_id),
+                },
+                GameEvent::Swapped {
+                    first_id,
+                    second_id,
+                } => write!(
+                    f,
+                    "Joueurs {} et {} échangent leurs positions",
+                    first_id, second_id
+                ),
+                GameEvent::Teleported {
+                    player_id,
+                    position,
+                } => write!(
+                    f,
+                    "Joueur {} se téléporte en ({}, {})",
+                    player_id, position.x, position.y
+                ),
+                GameEvent::Summoned {
+                    summoner_id,
+                    summon_id,
+                    creature_id,
+                    position,
+                } => write!(
+                    f,
+                    "Joueur {} invoque {} (joueur {}) en ({}, {})",
+                    summoner_id,
+                    summons::get(*creature_id).map_or("une créature", |c| c.name),
+                    summon_id,
+                    position.x,
+                    position.y
+                ),
+            }
+        }
+    }
+
+    /// Erreur de règle du jeu renvoyée au client lorsqu'une action est refusée
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub enum GameError {
+        /// Le joueur n'existe pas
+        PlayerNotFound { player_id: PlayerId },
+        /// Le joueur qui agit est mort
+        PlayerDead { player_id: PlayerId },
+        /// Ce n'est pas le tour du joueur
+        NotYourTurn { current_turn: PlayerId },
+        /// Pas assez de PM pour effectuer le déplacement
+        NotEnoughMovementPoints { required: u32, available: u32 },
+        /// Pas assez de PA pour effectuer l'action
+        NotEnoughActionPoints { required: u32, available: u32 },
+        /// La position est hors de la carte
+        InvalidPosition { position: Position },
+        /// La case est occupée par un autre joueur
+        CellOccupied { position: Position },
+        /// Un joueur ne peut pas se cibler lui-même
+        CannotTargetSelf,
+        /// La cible n'existe pas
+        TargetNotFound { target_id: PlayerId },
+        /// La cible est déjà morte
+        TargetAlreadyDead { target_id: PlayerId },
+        /// La cible est trop loin
+        OutOfRange { distance: u32, max_range: u32 },
+        /// La cible est plus proche que la portée minimale du sort
+        TooClose { distance: u32, min_range: u32 },
+        /// Le sort doit être lancé en ligne droite
+        NotInLine { target_cell: Position },
+        /// Le sort n'existe pas ou n'est pas connu du lanceur
+        UnknownSpell { spell_id: SpellId },
+        /// Le sort est en cours de relance
+        SpellOnCooldown {
+            spell_id: SpellId,
+            turns_remaining: u32,
+        },
+        /// Le sort a déjà été lancé le nombre de fois autorisé ce tour-ci
+        CastLimitReached { spell_id: SpellId, max_casts: u32 },
+        /// Le joueur est enraciné et ne peut pas se déplacer
+        Rooted { player_id: PlayerId },
+        /// La cible est un allié
+        FriendlyFire { target_id: PlayerId },
+        /// Le combat est terminé
+        FightOver,
+        /// Le combat n'a pas encore commencé : les joueurs se placent
+        FightNotStarted,
+        /// La phase de placement est terminée
+        NotInPlacement,
+        /// La case n'est pas une cellule de départ de l'équipe du joueur
+        InvalidStartingCell { position: Position },
+        /// La case est un trou ou un obstacle
+        CellNotWalkable { position: Position },
+        /// Le chemin ne part pas du joueur ou n'avance pas case par case
+        InvalidPath,
+        /// Aucun chemin libre ne mène à la case
+        NoPath { target: Position },
+        /// Un obstacle ou un combattant cache la case ciblée
+        NoLineOfSight { target_cell: Position },
+        /// Le combattant visé n'est ni le joueur ni une de ses invocations
+        NotYourFighter { fighter_id: PlayerId },
+        /// Le serveur ne traite pas ce type de message
+        UnsupportedMessage,
+    }
+
+    impl std::fmt::Display for GameError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                GameError::PlayerNotFound { player_id } => {
+                    write!(f, "Joueur {} introuvable", player_id)
+                }
+                GameError::PlayerDead { player_id } => {
+                    write!(f, "Le joueur {} est mort", player_id)
+                }
+                GameError::NotYourTurn { current_turn } => {
+                    write!(
+                        f,
+                        "Ce n'est pas votre tour (tour du joueur {})",
+                        current_turn
+                    )
+                }
+                GameError::NotEnoughMovementPoints {
+                    required,
+                    available,
+                } => write!(
+                    f,
+                    "Pas assez de PM ({} requis, {} disponibles)",
+                    required, available
+                ),
+                GameError::NotEnoughActionPoints {
+                    required,
+                    available,
+                } => write!(
+                    f,
+                    "Pas assez de PA ({} requis, {} disponibles)",
+                    required, available
+                ),
+                GameError::InvalidPosition { position } => {
+                    write!(f, "Position invalide ({}, {})", position.x, position.y)
+                }
+                GameError::CellOccupied { position } => {
+                    write!(f, "Position occupée ({}, {})", position.x, position.y)
+                }
+                GameError::CannotTargetSelf => write!(f, "Ne peut pas s'attaquer soi-même"),
+                GameError::TargetNotFound { target_id } => {
+                    write!(f, "Cible {} introuvable", target_id)
+                }
+                GameError::TargetAlreadyDead { target_id } => {
+                    write!(f, "La cible {} est déjà morte", target_id)
+                }
+                GameError::OutOfRange {
+                    distance,
+                    max_range,
+                } => write!(
+                    f,
+                    "Cible hors de portée ({} cases, portée {})",
+                    distance, max_range
+                ),
+                GameError::TooClose {
+                    distance,
+                    min_range,
+                } => write!(
+                    f,
+                    "Cible trop proche ({} cases, portée minimale {})",
+                    distance, min_range
+                ),
+                GameError::NotInLine { target_cell } => write!(
+                    f,
+                    "La case ({}, {}) n'est pas en ligne",
+                    target_cell.x, target_cell.y
+                ),
+                GameError::UnknownSpell { spell_id } => {
+                    write!(f, "Sort {} inconnu", spell_id)
+                }
+                GameError::SpellOnCooldown {
+                    spell_id,
+                    turns_remaining,
+                } => write!(
+                    f,
+                    "Sort {} en relance ({} tours restants)",
+                    spell_id, turns_remaining
+                ),
+                GameError::CastLimitReached {
+                    spell_id,
+                    max_casts,
+                } => write!(f, "Sort {} déjà lancé {} fois ce tour", spell_id, max_casts),
+                GameError::Rooted { player_id } => {
+                    write!(f, "Le joueur {} est enraciné", player_id)
+                }
+                GameError::FriendlyFire { target_id } => {
+                    write!(f, "Le joueur {} est un allié", target_id)
+                }
+                GameError::FightOver => write!(f, "Le combat est terminé"),
+                GameError::FightNotStarted => {
+                    write!(f, "Le combat n'a pas encore commencé")
+                }
+                GameError::NotInPlacement => write!(f, "La phase de placement est terminée"),
+                GameError::InvalidStartingCell { position } => write!(
+                    f,
+                    "La case ({}, {}) n'est pas une cellule de départ de votre équipe",
+                    position.x, position.y
+                ),
+                GameError::CellNotWalkable { position } => write!(
+                    f,
+                    "La case ({}, {}) n'est pas praticable",
+                    position.x, position.y
+                ),
+                GameError::InvalidPath => {
+                    write!(
+                        f,
+                        "Le chemin doit partir du joueur et avancer case par case"
+                    )
+                }
+                GameError::NoPath { target } => {
+                    write!(f, "Aucun chemin libre vers ({}, {})", target.x, target.y)
+                }
+                GameError::NoLineOfSight { target_cell } => write!(
+                    f,
+                    "La case ({}, {}) n'est pas en ligne de vue",
+                    target_cell.x, target_cell.y
+                ),
+                GameError::NotYourFighter { fighter_id } => {
+                    write!(
+                        f,
+                        "Le combattant {} n'est pas sous votre contrôle",
+                        fighter_id
+                    )
+                }
+                GameError::UnsupportedMessage => write!(f, "Message non géré"),
+            }
+        }
+    }
+
+    impl std::error::Error for GameError {}
+
+    /// Raison du refus d'une connexion par le serveur
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub enum RejectionReason {
+        /// La version du protocole du client n'est pas supportée
+        UnsupportedVersion {
+            client_version: u32,
+            supported_versions: Vec<u32>,
+        },
+        /// Le premier message reçu n'était pas un `Connect`
+        HandshakeRequired,
+        /// Le combat est terminé : plus personne ne peut le rejoindre
+        FightOver,
+        /// Plus aucune cellule de départ n'est libre
+        FightFull,
+    }
+
+    impl std::fmt::Display for RejectionReason {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                RejectionReason::UnsupportedVersion {
+                    client_version,
+                    supported_versions,
+                } => write!(
+                    f,
+                    "Version du protocole {} non supportée (versions acceptées : {:?})",
+                    client_version, supported_versions
+                ),
+                RejectionReason::HandshakeRequired => {
+                    write!(f, "Le client doit d'abord envoyer un message Connect")
+                }
+                RejectionReason::FightOver => write!(f, "Le combat est terminé"),
+                RejectionReason::FightFull => write!(f, "Le combat est complet"),
+            }
+        }
     }
 
     /// Messages réseau échangés entre client et serveur
     #[derive(Serialize, Deserialize, Debug, Clone)]
     pub enum Message {
-        /// Connexion d'un nouveau joueur
+        /// Connexion d'un nouveau joueur, premier message envoyé par le client
+        ///
+        /// Doit rester la première variante avec `protocol_version` en premier champ :
+        /// le serveur peut ainsi lire la version même si le reste du message a changé.
         Connect {
-            player_id: PlayerId,
+            protocol_version: u32,
+            client_build: String,
             player_name: String,
+            /// Formats de sérialisation acceptés par le client, par ordre de préférence
+            wire_formats: Vec<serialization::WireFormat>,
         },
         /// Déconnexion d'un joueur
         Disconnect { player_id: PlayerId },
         /// Déplacement d'un joueur
+        ///
+        /// Sans chemin, le serveur cherche le plus court ; un chemin explicite
+        /// commence par la position du joueur et finit par `target_position`.
         Move {
             player_id: PlayerId,
             target_position: Position,
+            path: Option<Vec<Position>>,
         },
         /// Attaque d'un joueur sur un autre
         Attack {
@@ -108,21 +1405,185 @@ pub mod protocol {
         },
         /// Fin du tour d'un joueur
         EndTurn { player_id: PlayerId },
-        /// Synchronisation de l'état du monde depuis le serveur
-        Sync { world_state: WorldState },
-        /// Message de confirmation ou d'erreur
-        Response { success: bool, message: String },
+        /// Lancer d'un sort sur une case
+        CastSpell {
+            caster_id: PlayerId,
+            spell_id: SpellId,
+            target_cell: Position,
+        },
+        /// Synchronisation complète de l'état du monde, en réponse à un `Resync`
+        Sync {
+            sequence: Sequence,
+            world_state: WorldState,
+        },
+        /// Résultat d'une action : message de confirmation ou erreur typée
+        ///
+        /// `request_id` reprend celui de la `Request` à l'origine de la réponse.
+        Response {
+            request_id: Option<RequestId>,
+            result: Result<String, GameError>,
+        },
         /// Message de bienvenue avec état initial
         Welcome {
             player_id: PlayerId,
+            /// Format choisi par le serveur pour les messages suivants
+            wire_format: serialization::WireFormat,
+            sequence: Sequence,
             world_state: WorldState,
+            /// Carte du combat, qui ne change plus ensuite
+            map: GameMap,
+        },
+        /// Refus de la connexion, envoyé à la place de `Welcome`
+        ConnectionRejected { reason: RejectionReason },
+        /// Enveloppe d'un message client portant un identifiant de requête
+        Request {
+            request_id: RequestId,
+            message: Box<Message>,
         },
+        /// Événements de combat produits par la dernière action
+        Events { events: Vec<GameEvent> },
+        /// Changements de l'état du monde depuis le dernier état envoyé
+        Delta { delta: WorldDelta },
+        /// Demande d'un état complet par un client désynchronisé
+        Resync,
+        /// Fin du combat : une équipe n'a plus de combattant vivant
+        FightEnded {
+            winners: Vec<PlayerId>,
+            losers: Vec<PlayerId>,
+        },
+        /// Choix d'une cellule de départ pendant le placement
+        ///
+        /// Si un allié occupe déjà la cellule, les deux joueurs échangent leur place.
+        Place { player_id: PlayerId, cell: Position },
+        /// Validation (ou annulation) du placement d'un joueur
+        Ready { player_id: PlayerId, ready: bool },
+    }
+
+    impl Message {
+        /// Enveloppe le message dans une `Request`
+        pub fn into_request(self, request_id: RequestId) -> Message {
+            Message::Request {
+                request_id,
+                message: Box::new(self),
+            }
+        }
+
+        /// Sépare l'identifiant de requête du message qu'il enveloppe
+        ///
+        /// Un message non enveloppé est retourné tel quel, sans identifiant.
+        pub fn unwrap_request(self) -> (Option<RequestId>, Message) {
+            match self {
+                Message::Request {
+                    request_id,
+                    message,
+                } => (Some(request_id), *message),
+                message => (None, message),
+            }
+        }
     }
 
     /// Fonctions utilitaires pour la sérialisation/désérialisation
     pub mod serialization {
         use super::Message;
         use bincode;
+        use serde::{Deserialize, Serialize};
+
+        /// Format de sérialisation des messages, négocié à la connexion
+        ///
+        /// Le `Connect` et la réponse du serveur (`Welcome` ou `ConnectionRejected`)
+        /// sont toujours en bincode ; le format négocié s'applique aux messages suivants.
+        /// JSON et MessagePack ne sont disponibles qu'avec les features `json` et `msgpack`.
+        #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub enum WireFormat {
+            #[default]
+            Bincode,
+            Json,
+            MessagePack,
+        }
+
+        impl WireFormat {
+            /// Indique si ce build sait encoder et décoder ce format
+            pub fn is_available(self) -> bool {
+                match self {
+                    WireFormat::Bincode => true,
+                    WireFormat::Json => cfg!(feature = "json"),
+                    WireFormat::MessagePack => cfg!(feature = "msgpack"),
+                }
+            }
+
+            /// Choisit le premier format de la liste de préférences disponible dans ce build
+            ///
+            /// Retombe sur bincode, toujours disponible.
+            pub fn negotiate(preferred: &[WireFormat]) -> WireFormat {
+                preferred
+                    .iter()
+                    .copied()
+                    .find(|format| format.is_available())
+                    .unwrap_or_default()
+            }
+        }
+
+        impl std::str::FromStr for WireFormat {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.to_ascii_lowercase().as_str() {
+                    "bincode" => Ok(WireFormat::Bincode),
+                    "json" => Ok(WireFormat::Json),
+                    "msgpack" | "messagepack" => Ok(WireFormat::MessagePack),
+                    other => Err(format!("Format inconnu: {}", other)),
+                }
+            }
+        }
+
+        impl std::fmt::Display for WireFormat {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    WireFormat::Bincode => write!(f, "bincode"),
+                    WireFormat::Json => write!(f, "json"),
+                    WireFormat::MessagePack => write!(f, "msgpack"),
+                }
+            }
+        }
+
+        /// Erreur de sérialisation, quel que soit le format
+        #[derive(Debug)]
+        pub enum SerializationError {
+            Bincode(bincode::Error),
+            #[cfg(feature = "json")]
+            Json(serde_json::Error),
+            #[cfg(feature = "msgpack")]
+            MessagePackEncode(rmp_serde::encode::Error),
+            #[cfg(feature = "msgpack")]
+            MessagePackDecode(rmp_serde::decode::Error),
+            /// Le format n'a pas été compilé dans ce build
+            UnavailableFormat(WireFormat),
+        }
+
+        impl std::fmt::Display for SerializationError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    SerializationError::Bincode(e) => write!(f, "bincode: {}", e),
+                    #[cfg(feature = "json")]
+                    SerializationError::Json(e) => write!(f, "json: {}", e),
+                    #[cfg(feature = "msgpack")]
+                    SerializationError::MessagePackEncode(e) => write!(f, "msgpack: {}", e),
+                    #[cfg(feature = "msgpack")]
+                    SerializationError::MessagePackDecode(e) => write!(f, "msgpack: {}", e),
+                    SerializationError::UnavailableFormat(format) => {
+                        write!(f, "Format {} non disponible dans ce build", format)
+                    }
+                }
+            }
+        }
+
+        impl std::error::Error for SerializationError {}
+
+        impl From<bincode::Error> for SerializationError {
+            fn from(error: bincode::Error) -> Self {
+                SerializationError::Bincode(error)
+            }
+        }
 
         /// Sérialise un message en bytes
         pub fn serialize(message: &Message) -> Result<Vec<u8>, bincode::Error> {
@@ -133,12 +1594,59 @@ pub mod protocol {
         pub fn deserialize(bytes: &[u8]) -> Result<Message, bincode::Error> {
             bincode::deserialize(bytes)
         }
+
+        /// Sérialise un message dans le format donné
+        pub fn serialize_with(
+            format: WireFormat,
+            message: &Message,
+        ) -> Result<Vec<u8>, SerializationError> {
+            match format {
+                WireFormat::Bincode => Ok(serialize(message)?),
+                #[cfg(feature = "json")]
+                WireFormat::Json => serde_json::to_vec(message).map_err(SerializationError::Json),
+                #[cfg(feature = "msgpack")]
+                WireFormat::MessagePack => {
+                    rmp_serde::to_vec_named(message).map_err(SerializationError::MessagePackEncode)
+                }
+                #[allow(unreachable_patterns)]
+                other => Err(SerializationError::UnavailableFormat(other)),
+            }
+        }
+
+        /// Désérialise un message depuis des bytes dans le format donné
+        pub fn deserialize_with(
+            format: WireFormat,
+            bytes: &[u8],
+        ) -> Result<Message, SerializationError> {
+            match format {
+                WireFormat::Bincode => Ok(deserialize(bytes)?),
+                #[cfg(feature = "json")]
+                WireFormat::Json => serde_json::from_slice(bytes).map_err(SerializationError::Json),
+                #[cfg(feature = "msgpack")]
+                WireFormat::MessagePack => {
+                    rmp_serde::from_slice(bytes).map_err(SerializationError::MessagePackDecode)
+                }
+                #[allow(unreachable_patterns)]
+                other => Err(SerializationError::UnavailableFormat(other)),
+            }
+        }
+
+        /// Lit la version du protocole d'un `Connect` sans le désérialiser entièrement
+        ///
+        /// Permet de répondre par un refus typé à un client dont le `Connect`
+        /// ne correspond plus au format actuel.
+        pub fn peek_protocol_version(bytes: &[u8]) -> Option<u32> {
+            let (variant, version): (u32, u32) = bincode::deserialize(bytes).ok()?;
+            // `Connect` est la variante d'index 0
+            (variant == 0).then_some(version)
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::protocol::*;
+    use super::summons;
 
     #[test]
     fn test_position_creation() {
@@ -167,6 +1675,29 @@ mod tests {
         assert!(player.is_alive);
     }
 
+    #[test]
+    fn test_player_state_vitality_feeds_max_health() {
+        let stats = Stats {
+            vitality: 50,
+            ..Default::default()
+        };
+        let player = PlayerState::with_stats(1, Position::new(0, 0), stats);
+        assert_eq!(player.max_health, 150);
+        assert_eq!(player.health, 150);
+        assert_eq!(player.stats, stats);
+
+        // Une vitalité très négative laisse au moins un point de vie
+        let frail = PlayerState::with_stats(
+            2,
+            Position::new(0, 0),
+            Stats {
+                vitality: -500,
+                ..Default::default()
+            },
+        );
+        assert_eq!(frail.max_health, 1);
+    }
+
     #[test]
     fn test_player_state_reset_turn() {
         let pos = Position::new(0, 0);
@@ -183,6 +1714,132 @@ mod tests {
         assert_eq!(player.movement_points, 3);
     }
 
+    #[test]
+    fn test_player_state_reset_turn_ticks_spells() {
+        let mut player = PlayerState::new(1, Position::new(0, 0));
+        let spell = crate::spells::get(crate::spells::EPEE_CELESTE).unwrap();
+        player.record_cast(spell);
+
+        let known = player.known_spell(spell.id).unwrap();
+        assert_eq!(known.casts_this_turn, 1);
+        assert_eq!(known.cooldown_remaining, spell.cooldown);
+
+        player.reset_turn();
+        let known = player.known_spell(spell.id).unwrap();
+        assert_eq!(known.casts_this_turn, 0);
+        assert_eq!(known.cooldown_remaining, spell.cooldown - 1);
+    }
+
+    fn effect(
+        kind: crate::effects::StatusKind,
+        caster_id: PlayerId,
+    ) -> crate::effects::ActiveEffect {
+        crate::effects::ActiveEffect {
+            kind,
+            caster_id,
+            spell_id: 0,
+            turns_remaining: 2,
+            dispellable: true,
+        }
+    }
+
+    #[test]
+    fn test_player_state_effects_modify_stats_and_points() {
+        use crate::effects::StatusKind;
+
+        let mut player = PlayerState::new(1, Position::new(0, 0));
+        player.effects = vec![
+            effect(StatusKind::ActionPoints { amount: 2 }, 1),
+            effect(StatusKind::MovementPoints { amount: -5 }, 2),
+            effect(StatusKind::DamageBoost { percent: 20 }, 1),
+            effect(
+                StatusKind::Resistance {
+                    element: Element::Fire,
+                    percent: -10,
+                },
+                2,
+            ),
+        ];
+
+        player.reset_turn();
+        assert_eq!(player.action_points, 8);
+        assert_eq!(player.movement_points, 0);
+        assert_eq!(player.effective_stats().strength, 20);
+        assert_eq!(player.effective_stats().vitality, 0);
+        assert_eq!(player.effective_resistances().fire.percent, -10);
+        assert_eq!(player.effective_resistances().earth.percent, 0);
+        assert!(!player.is_rooted());
+    }
+
+    #[test]
+    fn test_player_state_shields_absorb_in_order() {
+        use crate::effects::StatusKind;
+
+        let mut player = PlayerState::new(1, Position::new(0, 0));
+        player.effects = vec![
+            effect(StatusKind::Shield { amount: 10 }, 1),
+            effect(StatusKind::Rooted, 1),
+            effect(StatusKind::Shield { amount: 30 }, 1),
+        ];
+
+        assert_eq!(player.absorb_damage(25), 25);
+        assert_eq!(
+            player.effects,
+            vec![
+                effect(StatusKind::Rooted, 1),
+                effect(StatusKind::Shield { amount: 15 }, 1),
+            ]
+        );
+        assert_eq!(player.absorb_damage(40), 15);
+        assert_eq!(player.effects, vec![effect(StatusKind::Rooted, 1)]);
+    }
+
+    #[test]
+    fn test_player_state_dispel_keeps_undispellable_effects() {
+        use crate::effects::StatusKind;
+
+        let mut player = PlayerState::new(1, Position::new(0, 0));
+        let mut immunity = effect(StatusKind::Invulnerable, 1);
+        immunity.dispellable = false;
+        player.effects = vec![effect(StatusKind::Rooted, 2), immunity.clone()];
+
+        assert!(player.dispel());
+        assert_eq!(player.effects, vec![immunity]);
+        assert!(!player.dispel());
+    }
+
+    #[test]
+    fn test_world_state_effects_expire_on_caster_turns() {
+        use crate::effects::StatusKind;
+
+        let mut world = WorldState::new(10, 10);
+        world.players.push(PlayerState::new(1, Position::new(0, 0)));
+        world.players.push(PlayerState::new(2, Position::new(5, 5)));
+        world.get_player_mut(2).unwrap().effects = vec![
+            effect(StatusKind::Rooted, 1),
+            effect(StatusKind::Shield { amount: 10 }, 2),
+        ];
+
+        // Le tour de la cible ne décompte pas les effets posés par un autre
+        world.expire_effects(2);
+        assert_eq!(world.get_player(2).unwrap().effects[0].turns_remaining, 2);
+        assert_eq!(world.get_player(2).unwrap().effects[1].turns_remaining, 1);
+
+        world.expire_effects(1);
+        world.expire_effects(1);
+        assert_eq!(
+            world.get_player(2).unwrap().effects,
+            vec![{
+                let mut shield = effect(StatusKind::Shield { amount: 10 }, 2);
+                shield.turns_remaining = 1;
+                shield
+            }]
+        );
+
+        world.remove_effects_from(2);
+        assert!(world.get_player(2).unwrap().effects.is_empty());
+    }
+
     #[test]
     fn test_world_state_creation() {
         let world = WorldState::new(10, 10);
@@ -207,11 +1864,265 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[test]
+    fn test_moved_event_display() {
+        let mut event = GameEvent::Moved {
+            player_id: 1,
+            path: vec![Position::new(0, 0), Position::new(1, 0)],
+            tackle: None,
+        };
+        assert_eq!(event.to_string(), "Joueur 1 se déplace en (1, 0)");
+
+        if let GameEvent::Moved { tackle, .. } = &mut event {
+            *tackle = Some(TackleLoss {
+                movement_points: 2,
+                action_points: 3,
+            });
+        }
+        assert_eq!(
+            event.to_string(),
+            "Joueur 1 se déplace en (1, 0), taclé (-2 PM, -3 PA)"
+        );
+    }
+
+    #[test]
+    fn test_world_state_apply_events() {
+        let mut world = WorldState::new(10, 10);
+        world.players.push(PlayerState::new(1, Position::new(0, 0)));
+        world.players.push(PlayerState::new(2, Position::new(5, 5)));
+
+        let events = vec![
+            GameEvent::Moved {
+                player_id: 1,
+                path: vec![
+                    Position::new(0, 0),
+                    Position::new(1, 0),
+                    Position::new(1, 1),
+                ],
+                tackle: None,
+            },
+            GameEvent::ApMpChanged {
+                player_id: 1,
+                action_points: 6,
+                movement_points: 1,
+            },
+            GameEvent::Damaged {
+                target_id: 2,
+                source_id: 1,
+                amount: 30,
+            },
+            GameEvent::TurnEnded { player_id: 1 },
+            GameEvent::TurnStarted {
+                player_id: 2,
+                turn_number: 2,
+                time_limit: 30,
+            },
+        ];
+        for event in &events {
+            world.apply_event(event);
+        }
+
+        let player1 = world.get_player(1).unwrap();
+        assert_eq!(player1.position, Position::new(1, 1));
+        assert_eq!(player1.movement_points, 1);
+        assert_eq!(world.get_player(2).unwrap().health, 70);
+        assert_eq!(world.current_turn, 2);
+        assert_eq!(world.turn_number, 2);
+        assert_eq!(world.turn_time_remaining, 30);
+
+        world.apply_event(&GameEvent::SpellCast {
+            caster_id: 2,
+            spell_id: crate::spells::MOT_SOIGNANT,
+            target_cell: Position::new(5, 5),
+        });
+        world.apply_event(&GameEvent::Healed {
+            target_id: 2,
+            source_id: 2,
+            amount: 50,
+        });
+        let player2 = world.get_player(2).unwrap();
+        assert_eq!(player2.health, 100);
+        assert_eq!(
+            player2
+                .known_spell(crate::spells::MOT_SOIGNANT)
+                .unwrap()
+                .casts_this_turn,
+            1
+        );
+
+        world.apply_event(&GameEvent::Died { player_id: 2 });
+        let player2 = world.get_player(2).unwrap();
+        assert!(!player2.is_alive);
+        assert_eq!(player2.health, 0);
+    }
+
+    #[test]
+    fn test_world_state_apply_placement_events() {
+        let mut world = WorldState::new(10, 10);
+        world.players.push(PlayerState::new(1, Position::new(1, 1)));
+        world.players.push(PlayerState::new(2, Position::new(8, 1)));
+
+        for event in [
+            GameEvent::Placed {
+                player_id: 1,
+                position: Position::new(1, 3),
+            },
+            GameEvent::ReadyChanged {
+                player_id: 2,
+                ready: true,
+            },
+            GameEvent::FightStarted {
+                timeline: vec![2, 1],
+            },
+            GameEvent::AfkChanged {
+                player_id: 1,
+                afk: true,
+            },
+        ] {
+            world.apply_event(&event);
+        }
+
+        assert_eq!(world.get_player(1).unwrap().position, Position::new(1, 3));
+        assert!(world.get_player(2).unwrap().ready);
+        assert!(world.get_player(1).unwrap().afk);
+        assert_eq!(world.timeline, vec![2, 1]);
+        assert_eq!(world.phase, FightPhase::Fighting);
+    }
+
+    #[test]
+    fn test_world_state_apply_forced_movement_events() {
+        let mut world = WorldState::new(10, 10);
+        world.players.push(PlayerState::new(1, Position::new(1, 1)));
+        world.players.push(PlayerState::new(2, Position::new(2, 1)));
+
+        world.apply_event(&GameEvent::Pushed {
+            player_id: 2,
+            path: vec![Position::new(2, 1), Position::new(3, 1)],
+        });
+        assert_eq!(world.get_player(2).unwrap().position, Position::new(3, 1));
+
+        world.apply_event(&GameEvent::Swapped {
+            first_id: 1,
+            second_id: 2,
+        });
+        assert_eq!(world.get_player(1).unwrap().position, Position::new(3, 1));
+        assert_eq!(world.get_player(2).unwrap().position, Position::new(1, 1));
+
+        world.apply_event(&GameEvent::Teleported {
+            player_id: 1,
+            position: Position::new(6, 6),
+        });
+        assert_eq!(world.get_player(1).unwrap().position, Position::new(6, 6));
+    }
+
+    #[test]
+    fn test_summons_play_right_after_their_summoner() {
+        let mut world = WorldState::new(10, 10);
+        for id in 1..=3 {
+            world
+                .players
+                .push(PlayerState::new(id, Position::new(id as i32, 1)));
+        }
+        world.timeline = vec![1, 2, 3];
+
+        for (summon_id, x) in [(4, 1), (5, 2)] {
+            world.apply_event(&GameEvent::Summoned {
+                summoner_id: 1,
+                summon_id,
+                creature_id: summons::TOFU,
+                position: Position::new(x, 2),
+            });
+        }
+        // Les invocations suivent leur invocateur, dans l'ordre où elles ont été appelées
+        assert_eq!(world.timeline, vec![1, 4, 5, 2, 3]);
+
+        let summon = world.get_player(5).unwrap();
+        assert_eq!(summon.summoner(), Some(1));
+        assert_eq!(summon.team, world.get_player(1).unwrap().team);
+        assert_eq!(
+            summon.spells.len(),
+            summons::get(summons::TOFU).unwrap().spells.len()
+        );
+        assert!(world.get_player(1).unwrap().summoner().is_none());
+    }
+
+    #[test]
+    fn test_world_delta_roundtrip() {
+        let mut old = WorldState::new(10, 10);
+        old.players.push(PlayerState::new(1, Position::new(0, 0)));
+        old.players.push(PlayerState::new(2, Position::new(5, 5)));
+        old.current_turn = 1;
+
+        let mut new = old.clone();
+        new.get_player_mut(1).unwrap().position = Position::new(1, 0);
+        new.get_player_mut(1).unwrap().movement_points = 2;
+        new.get_player_mut(1).unwrap().spells[0].cooldown_remaining = 1;
+        new.players.retain(|p| p.id != 2);
+        new.players.push(PlayerState::new(3, Position::new(9, 9)));
+        new.timeline = vec![1, 3];
+        new.current_turn = 3;
+        new.phase = FightPhase::Fighting;
+
+        let delta = WorldDelta::between(&old, &new, 4, 5);
+        assert_eq!(delta.base_sequence, 4);
+        assert_eq!(delta.sequence, 5);
+        assert_eq!(delta.current_turn, Some(3));
+        assert_eq!(delta.turn_number, None);
+        assert_eq!(delta.timeline, Some(vec![1, 3]));
+        assert_eq!(delta.phase, Some(FightPhase::Fighting));
+        assert_eq!(delta.removed_players, vec![2]);
+        assert_eq!(delta.added_players.len(), 1);
+        assert_eq!(
+            delta.updated_players,
+            vec![PlayerDelta {
+                id: 1,
+                position: Some(Position::new(1, 0)),
+                movement_points: Some(2),
+                spells: Some(new.get_player(1).unwrap().spells.clone()),
+                ..Default::default()
+            }]
+        );
+
+        let mut replica = old.clone();
+        replica.apply_delta(&delta);
+        assert_eq!(replica, new);
+    }
+
+    #[test]
+    fn test_world_delta_empty_when_unchanged() {
+        let mut world = WorldState::new(10, 10);
+        world.players.push(PlayerState::new(1, Position::new(0, 0)));
+
+        let delta = WorldDelta::between(&world, &world.clone(), 1, 2);
+        assert!(delta.is_empty());
+    }
+
+    #[test]
+    fn test_world_delta_serialization() {
+        let old = WorldState::new(10, 10);
+        let mut new = old.clone();
+        new.players.push(PlayerState::new(1, Position::new(2, 3)));
+
+        let message = Message::Delta {
+            delta: WorldDelta::between(&old, &new, 0, 1),
+        };
+        let serialized = serialization::serialize(&message).unwrap();
+        match serialization::deserialize(&serialized).unwrap() {
+            Message::Delta { delta } => {
+                let mut replica = old.clone();
+                replica.apply_delta(&delta);
+                assert_eq!(replica, new);
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
     #[test]
     fn test_message_serialization() {
         let message = Message::Move {
             player_id: 1,
             target_position: Position::new(5, 5),
+            path: Some(vec![Position::new(5, 4), Position::new(5, 5)]),
         };
 
         let serialized = serialization::serialize(&message).unwrap();
@@ -222,20 +2133,220 @@ mod tests {
             Message::Move {
                 player_id,
                 target_position,
+                path,
             } => {
                 assert_eq!(player_id, 1);
                 assert_eq!(target_position, Position::new(5, 5));
+                assert_eq!(path.map(|path| path.len()), Some(2));
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_connect_message_serialization() {
+        let message = Message::Connect {
+            protocol_version: PROTOCOL_VERSION,
+            client_build: "0.1.0".to_string(),
+            player_name: "Iop".to_string(),
+            wire_formats: vec![serialization::WireFormat::Json],
+        };
+
+        let serialized = serialization::serialize(&message).unwrap();
+        assert_eq!(
+            serialization::peek_protocol_version(&serialized),
+            Some(PROTOCOL_VERSION)
+        );
+
+        match serialization::deserialize(&serialized).unwrap() {
+            Message::Connect {
+                protocol_version,
+                client_build,
+                player_name,
+                wire_formats,
+            } => {
+                assert_eq!(protocol_version, PROTOCOL_VERSION);
+                assert_eq!(client_build, "0.1.0");
+                assert_eq!(player_name, "Iop");
+                assert_eq!(wire_formats, vec![serialization::WireFormat::Json]);
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_peek_protocol_version_ignores_other_messages() {
+        let message = Message::EndTurn { player_id: 1 };
+        let serialized = serialization::serialize(&message).unwrap();
+        assert_eq!(serialization::peek_protocol_version(&serialized), None);
+    }
+
+    #[test]
+    fn test_protocol_version_supported() {
+        assert!(is_protocol_version_supported(PROTOCOL_VERSION));
+        assert!(!is_protocol_version_supported(PROTOCOL_VERSION + 1));
+    }
+
+    #[test]
+    fn test_response_error_serialization() {
+        let error = GameError::NotEnoughMovementPoints {
+            required: 4,
+            available: 3,
+        };
+        let message = Message::Response {
+            request_id: Some(7),
+            result: Err(error.clone()),
+        };
+
+        let serialized = serialization::serialize(&message).unwrap();
+        match serialization::deserialize(&serialized).unwrap() {
+            Message::Response { request_id, result } => {
+                assert_eq!(request_id, Some(7));
+                assert_eq!(result, Err(error));
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_request_envelope_roundtrip() {
+        let request = Message::EndTurn { player_id: 3 }.into_request(42);
+
+        let serialized = serialization::serialize(&request).unwrap();
+        let deserialized = serialization::deserialize(&serialized).unwrap();
+
+        match deserialized.unwrap_request() {
+            (Some(42), Message::EndTurn { player_id: 3 }) => {}
+            other => panic!("Wrong request: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cast_spell_serialization() {
+        let message = Message::CastSpell {
+            caster_id: 1,
+            spell_id: crate::spells::FLECHE_MAGIQUE,
+            target_cell: Position::new(4, 2),
+        };
+
+        let serialized = serialization::serialize(&message).unwrap();
+        match serialization::deserialize(&serialized).unwrap() {
+            Message::CastSpell {
+                caster_id,
+                spell_id,
+                target_cell,
+            } => {
+                assert_eq!(caster_id, 1);
+                assert_eq!(spell_id, crate::spells::FLECHE_MAGIQUE);
+                assert_eq!(target_cell, Position::new(4, 2));
             }
             _ => panic!("Wrong message type"),
         }
     }
 
+    #[test]
+    fn test_unwrap_request_without_envelope() {
+        let (request_id, message) = Message::EndTurn { player_id: 3 }.unwrap_request();
+        assert_eq!(request_id, None);
+        assert!(matches!(message, Message::EndTurn { player_id: 3 }));
+    }
+
+    #[test]
+    fn test_game_error_display() {
+        let error = GameError::NotEnoughActionPoints {
+            required: 3,
+            available: 1,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Pas assez de PA (3 requis, 1 disponibles)"
+        );
+        assert_eq!(
+            GameError::NotYourFighter { fighter_id: 4 }.to_string(),
+            "Le combattant 4 n'est pas sous votre contrôle"
+        );
+    }
+
+    #[test]
+    fn test_wire_format_negotiation() {
+        use serialization::WireFormat;
+
+        assert_eq!(WireFormat::negotiate(&[]), WireFormat::Bincode);
+        assert_eq!(
+            WireFormat::negotiate(&[WireFormat::Bincode, WireFormat::Json]),
+            WireFormat::Bincode
+        );
+
+        let expected = if cfg!(feature = "json") {
+            WireFormat::Json
+        } else {
+            WireFormat::Bincode
+        };
+        assert_eq!(WireFormat::negotiate(&[WireFormat::Json]), expected);
+    }
+
+    #[test]
+    fn test_wire_format_from_str() {
+        use serialization::WireFormat;
+
+        assert_eq!("JSON".parse(), Ok(WireFormat::Json));
+        assert_eq!("msgpack".parse(), Ok(WireFormat::MessagePack));
+        assert!("xml".parse::<WireFormat>().is_err());
+    }
+
+    #[test]
+    fn test_serialize_with_every_available_format() {
+        use serialization::{deserialize_with, serialize_with, WireFormat};
+
+        let message = Message::Response {
+            request_id: Some(3),
+            result: Err(GameError::CannotTargetSelf),
+        };
+
+        for format in [
+            WireFormat::Bincode,
+            WireFormat::Json,
+            WireFormat::MessagePack,
+        ] {
+            let bytes = serialize_with(format, &message);
+            if !format.is_available() {
+                assert!(bytes.is_err());
+                continue;
+            }
+
+            match deserialize_with(format, &bytes.unwrap()).unwrap() {
+                Message::Response { request_id, result } => {
+                    assert_eq!(request_id, Some(3));
+                    assert_eq!(result, Err(GameError::CannotTargetSelf));
+                }
+                _ => panic!("Wrong message type"),
+            }
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_is_readable() {
+        let bytes = serialization::serialize_with(
+            serialization::WireFormat::Json,
+            &Message::EndTurn { player_id: 4 },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            r#"{"EndTurn":{"player_id":4}}"#
+        );
+    }
+
     #[test]
     fn test_welcome_message_serialization() {
         let world = WorldState::new(10, 10);
         let message = Message::Welcome {
             player_id: 1,
+            wire_format: serialization::WireFormat::Bincode,
+            sequence: 1,
             world_state: world.clone(),
+            map: crate::map::GameMap::new(10, 10),
         };
 
         let serialized = serialization::serialize(&message).unwrap();
@@ -244,11 +2355,16 @@ mod tests {
         match deserialized {
             Message::Welcome {
                 player_id,
+                sequence,
                 world_state,
+                map,
+                ..
             } => {
                 assert_eq!(player_id, 1);
+                assert_eq!(sequence, 1);
                 assert_eq!(world_state.map_width, 10);
                 assert_eq!(world_state.map_height, 10);
+                assert_eq!(map, crate::map::GameMap::new(10, 10));
             }
             _ => panic!("Wrong message type"),
         }
@@ -0,0 +1,192 @@
+//! Ligne de vue entre deux cases
+//!
+//! La ligne de vue suit le segment qui relie les centres des deux cases :
+//! toutes les cases qu'il traverse (algorithme de Bresenham « supercover »)
+//! doivent être libres. Une case bloque si c'est un obstacle ou si un
+//! combattant s'y trouve ; les trous ne bloquent pas. Les cases du lanceur et
+//! de la cible ne comptent pas.
+//!
+//! Comme dans Dofus, un segment qui passe exactement par le coin de deux
+//! cases se faufile entre elles : il n'est bloqué que si les deux cases
+//! bloquent.
+
+use crate::map::GameMap;
+use crate::protocol::Position;
+
+/// Case (ou paire de cases) traversée par un segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crossing {
+    /// Case traversée par le segment
+    Cell(Position),
+    /// Cases qui bordent un coin par lequel passe exactement le segment
+    Corner(Position, Position),
+}
+
+/// Cases traversées par le segment entre les centres de `from` et `to`, extrémités exclues
+pub fn crossings(from: Position, to: Position) -> Vec<Crossing> {
+    let (dx, dy) = ((to.x - from.x).abs(), (to.y - from.y).abs());
+    let (sx, sy) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+    let mut crossings = Vec::new();
+    let (mut x, mut y) = (from.x, from.y);
+    let (mut ix, mut iy) = (0, 0);
+
+    while ix < dx || iy < dy {
+        // Compare les instants où le segment franchit le prochain bord vertical
+        // (x) et le prochain bord horizontal (y), en restant en entiers
+        let decision = (1 + 2 * ix) * dy - (1 + 2 * iy) * dx;
+        if decision == 0 {
+            crossings.push(Crossing::Corner(
+                Position::new(x + sx, y),
+                Position::new(x, y + sy),
+            ));
+            x += sx;
+            y += sy;
+            ix += 1;
+            iy += 1;
+        } else if decision < 0 {
+            x += sx;
+            ix += 1;
+        } else {
+            y += sy;
+            iy += 1;
+        }
+
+        let cell = Position::new(x, y);
+        if cell != to {
+            crossings.push(Crossing::Cell(cell));
+        }
+    }
+    crossings
+}
+
+/// Indique si `to` est visible depuis `from`
+///
+/// `is_occupied` indique les cases où se tient un combattant.
+pub fn has_line_of_sight(
+    map: &GameMap,
+    from: Position,
+    to: Position,
+    is_occupied: impl Fn(Position) -> bool,
+) -> bool {
+    let blocks = |cell: Position| map.blocks_line_of_sight(cell) || is_occupied(cell);
+    crossings(from, to)
+        .into_iter()
+        .all(|crossing| match crossing {
+            Crossing::Cell(cell) => !blocks(cell),
+            Crossing::Corner(a, b) => !(blocks(a) && blocks(b)),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Carte de référence : obstacles en (3, 3), (3, 4) et (2, 5), trou en (1, 1)
+    fn reference_map() -> GameMap {
+        GameMap::from_layout(
+            7,
+            7,
+            "
+            r......
+            .o.....
+            .......
+            ...#...
+            ...#...
+            ..#...b
+            .......
+            ",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_crossings() {
+        assert_eq!(
+            crossings(Position::new(1, 3), Position::new(5, 1)),
+            vec![
+                Crossing::Cell(Position::new(2, 3)),
+                Crossing::Cell(Position::new(2, 2)),
+                Crossing::Cell(Position::new(3, 2)),
+                Crossing::Cell(Position::new(4, 2)),
+                Crossing::Cell(Position::new(4, 1)),
+            ]
+        );
+        assert_eq!(
+            crossings(Position::new(0, 0), Position::new(1, 1)),
+            vec![Crossing::Corner(Position::new(1, 0), Position::new(0, 1))]
+        );
+        assert!(crossings(Position::new(2, 2), Position::new(2, 3)).is_empty());
+        assert!(crossings(Position::new(2, 2), Position::new(2, 2)).is_empty());
+    }
+
+    #[test]
+    fn test_reference_map() {
+        let map = reference_map();
+        let caster = Position::new(1, 3);
+        let visible = |target: Position| has_line_of_sight(&map, caster, target, |_| false);
+
+        // Droit à travers l'obstacle
+        assert!(!visible(Position::new(5, 3)));
+        // Le segment frôle l'obstacle sans le traverser
+        assert!(visible(Position::new(5, 1)));
+        // Le segment traverse l'obstacle en diagonale
+        assert!(!visible(Position::new(5, 2)));
+        // Les trous ne bloquent pas
+        assert!(visible(Position::new(1, 0)));
+        // Les deux cases qui bordent le coin sont des obstacles
+        assert!(!visible(Position::new(3, 5)));
+        // La cible elle-même peut être un obstacle
+        assert!(visible(Position::new(3, 3)));
+    }
+
+    #[test]
+    fn test_occupied_cells_block() {
+        let map = reference_map();
+        let from = Position::new(0, 6);
+        let occupied = |cell: Position| cell == Position::new(2, 6);
+
+        assert!(has_line_of_sight(&map, from, Position::new(4, 6), |_| {
+            false
+        }));
+        assert!(!has_line_of_sight(
+            &map,
+            from,
+            Position::new(4, 6),
+            occupied
+        ));
+        // Le combattant visé ne se cache pas lui-même
+        assert!(has_line_of_sight(&map, from, Position::new(2, 6), occupied));
+    }
+
+    #[test]
+    fn test_corner_blocked_only_by_both_cells() {
+        let map = GameMap::new(5, 5);
+        let (from, to) = (Position::new(0, 0), Position::new(2, 2));
+
+        let one_side = |cell: Position| cell == Position::new(1, 0);
+        assert!(has_line_of_sight(&map, from, to, one_side));
+
+        let both_sides =
+            |cell: Position| cell == Position::new(1, 0) || cell == Position::new(0, 1);
+        assert!(!has_line_of_sight(&map, from, to, both_sides));
+    }
+
+    #[test]
+    fn test_line_of_sight_is_symmetric() {
+        let map = reference_map();
+        let cells: Vec<Position> = (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| Position::new(x, y)))
+            .collect();
+        for &a in &cells {
+            for &b in &cells {
+                assert_eq!(
+                    has_line_of_sight(&map, a, b, |_| false),
+                    has_line_of_sight(&map, b, a, |_| false),
+                    "{:?} -> {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! Carte d'un combat : grille de cellules et cellules de départ
+//!
+//! La carte ne change pas pendant le combat : le serveur l'envoie une seule
+//! fois, dans le `Welcome`. Elle est stockée en base sous forme de plan
+//! textuel, une ligne de caractères par rangée de cellules :
+//!
+//! - `.` : cellule praticable ;
+//! - `o` : trou, infranchissable mais sans bloquer la ligne de vue ;
+//! - `#` : obstacle, infranchissable et bloquant la ligne de vue ;
+//! - `r` / `b` : cellule de départ praticable de l'équipe rouge / bleue.
+
+use crate::protocol::{Position, Team};
+use serde::{Deserialize, Serialize};
+
+/// Nombre de cellules de départ par équipe sur une carte générée
+pub const STARTING_CELLS_PER_TEAM: usize = 4;
+
+/// Nature d'une cellule de la carte
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CellType {
+    /// Cellule sur laquelle on peut se déplacer et lancer des sorts
+    #[default]
+    Walkable,
+    /// Trou : infranchissable, mais on peut lancer des sorts par-dessus
+    Hole,
+    /// Obstacle : infranchissable et bloque la ligne de vue
+    Obstacle,
+}
+
+impl CellType {
+    /// Indique si un combattant peut se tenir sur la cellule
+    pub fn is_walkable(self) -> bool {
+        self == CellType::Walkable
+    }
+
+    /// Indique si la cellule bloque la ligne de vue
+    pub fn blocks_line_of_sight(self) -> bool {
+        self == CellType::Obstacle
+    }
+}
+
+/// Erreur de lecture du plan d'une carte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// Le plan n'a pas les dimensions annoncées
+    WrongSize { width: i32, height: i32 },
+    /// Caractère inconnu dans le plan
+    UnknownCell { character: char },
+    /// Une équipe n'a aucune cellule de départ
+    MissingStartingCells { team: Team },
+}
+
+impl std::fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutError::WrongSize { width, height } => {
+                write!(f, "Le plan ne fait pas {}x{} cellules", width, height)
+            }
+            LayoutError::UnknownCell { character } => {
+                write!(f, "Cellule inconnue '{}' dans le plan", character)
+            }
+            LayoutError::MissingStartingCells { team } => {
+                write!(f, "Aucune cellule de départ pour l'équipe {}", team)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Carte d'un combat
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GameMap {
+    pub width: i32,
+    pub height: i32,
+    /// Cellules rangée par rangée, `width * height` au total
+    pub cells: Vec<CellType>,
+    /// Cellules où l'équipe rouge se place avant le combat
+    pub red_starting_cells: Vec<Position>,
+    /// Cellules où l'équipe bleue se place avant le combat
+    pub blue_starting_cells: Vec<Position>,
+}
+
+impl GameMap {
+    /// Génère une carte entièrement praticable avec les cellules de départ face à face
+    ///
+    /// Les rouges se placent sur la deuxième colonne, les bleus sur
+    /// l'avant-dernière, une ligne sur deux.
+    pub fn new(width: i32, height: i32) -> Self {
+        let rows = || {
+            (1..height)
+                .step_by(2)
+                .take(STARTING_CELLS_PER_TEAM)
+                .collect::<Vec<_>>()
+        };
+        let left = 1.min(width - 1);
+        let right = (width - 2).max(0);
+        Self {
+            width,
+            height,
+            cells: vec![CellType::Walkable; (width.max(0) * height.max(0)) as usize],
+            red_starting_cells: rows().into_iter().map(|y| Position::new(left, y)).collect(),
+            blue_starting_cells: rows()
+                .into_iter()
+                .map(|y| Position::new(right, y))
+                .collect(),
+        }
+    }
+
+    /// Lit une carte depuis son plan textuel (voir la documentation du module)
+    ///
+    /// Les cellules de départ sont numérotées dans l'ordre de lecture du plan.
+    pub fn from_layout(width: i32, height: i32, layout: &str) -> Result<Self, LayoutError> {
+        let rows: Vec<&str> = layout
+            .lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .collect();
+        let wrong_size = LayoutError::WrongSize { width, height };
+        if rows.len() != height as usize {
+            return Err(wrong_size);
+        }
+
+        let mut map = Self {
+            width,
+            height,
+            cells: Vec::with_capacity((width * height) as usize),
+            red_starting_cells: Vec::new(),
+            blue_starting_cells: Vec::new(),
+        };
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width as usize {
+                return Err(wrong_size);
+            }
+            for (x, character) in row.chars().enumerate() {
+                let position = Position::new(x as i32, y as i32);
+                let cell = match character {
+                    '.' => CellType::Walkable,
+                    'o' => CellType::Hole,
+                    '#' => CellType::Obstacle,
+                    'r' => {
+                        map.red_starting_cells.push(position);
+                        CellType::Walkable
+                    }
+                    'b' => {
+                        map.blue_starting_cells.push(position);
+                        CellType::Walkable
+                    }
+                    character => return Err(LayoutError::UnknownCell { character }),
+                };
+                map.cells.push(cell);
+            }
+        }
+
+        for team in [Team::Red, Team::Blue] {
+            if map.starting_cells(team).is_empty() {
+                return Err(LayoutError::MissingStartingCells { team });
+            }
+        }
+        Ok(map)
+    }
+
+    /// Cellules de départ d'une équipe
+    pub fn starting_cells(&self, team: Team) -> &[Position] {
+        match team {
+            Team::Red => &self.red_starting_cells,
+            Team::Blue => &self.blue_starting_cells,
+        }
+    }
+
+    /// Indique si une position est dans les limites de la carte
+    pub fn contains(&self, position: Position) -> bool {
+        position.x >= 0 && position.x < self.width && position.y >= 0 && position.y < self.height
+    }
+
+    /// Nature de la cellule à une position, `None` hors de la carte
+    pub fn cell(&self, position: Position) -> Option<CellType> {
+        if !self.contains(position) {
+            return None;
+        }
+        self.cells
+            .get((position.y * self.width + position.x) as usize)
+            .copied()
+    }
+
+    /// Indique si un combattant peut se tenir sur une position
+    pub fn is_walkable(&self, position: Position) -> bool {
+        self.cell(position).is_some_and(CellType::is_walkable)
+    }
+
+    /// Indique si la cellule à une position bloque la ligne de vue
+    pub fn blocks_line_of_sight(&self, position: Position) -> bool {
+        self.cell(position)
+            .is_some_and(CellType::blocks_line_of_sight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_starting_cells() {
+        let map = GameMap::new(10, 10);
+        assert_eq!(
+            map.starting_cells(Team::Red),
+            &[
+                Position::new(1, 1),
+                Position::new(1, 3),
+                Position::new(1, 5),
+                Position::new(1, 7),
+            ]
+        );
+        assert_eq!(map.starting_cells(Team::Blue)[0], Position::new(8, 1));
+        for team in [Team::Red, Team::Blue] {
+            assert!(map
+                .starting_cells(team)
+                .iter()
+                .all(|&cell| map.is_walkable(cell)));
+        }
+    }
+
+    #[test]
+    fn test_small_map_has_fewer_starting_cells() {
+        let map = GameMap::new(3, 4);
+        assert_eq!(map.red_starting_cells.len(), 2);
+        assert!(map
+            .blue_starting_cells
+            .iter()
+            .all(|&cell| map.contains(cell)));
+    }
+
+    #[test]
+    fn test_from_layout() {
+        let map = GameMap::from_layout(
+            4,
+            3,
+            "
+            r.#b
+            .o..
+            r..b
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(map.cell(Position::new(2, 0)), Some(CellType::Obstacle));
+        assert_eq!(map.cell(Position::new(1, 1)), Some(CellType::Hole));
+        assert_eq!(map.cell(Position::new(4, 0)), None);
+        assert!(!map.is_walkable(Position::new(1, 1)));
+        assert!(!map.blocks_line_of_sight(Position::new(1, 1)));
+        assert!(map.blocks_line_of_sight(Position::new(2, 0)));
+        assert_eq!(
+            map.starting_cells(Team::Red),
+            &[Position::new(0, 0), Position::new(0, 2)]
+        );
+        assert_eq!(
+            map.starting_cells(Team::Blue),
+            &[Position::new(3, 0), Position::new(3, 2)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_layouts() {
+        assert_eq!(
+            GameMap::from_layout(3, 2, "r.b\n..."),
+            Ok(GameMap {
+                width: 3,
+                height: 2,
+                cells: vec![CellType::Walkable; 6],
+                red_starting_cells: vec![Position::new(0, 0)],
+                blue_starting_cells: vec![Position::new(2, 0)],
+            })
+        );
+        assert_eq!(
+            GameMap::from_layout(3, 2, "r.b"),
+            Err(LayoutError::WrongSize {
+                width: 3,
+                height: 2
+            })
+        );
+        assert_eq!(
+            GameMap::from_layout(3, 2, "r.b\n.x."),
+            Err(LayoutError::UnknownCell { character: 'x' })
+        );
+        assert_eq!(
+            GameMap::from_layout(3, 2, "r..\n..."),
+            Err(LayoutError::MissingStartingCells { team: Team::Blue })
+        );
+    }
+}
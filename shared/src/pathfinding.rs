@@ -0,0 +1,165 @@
+//! Recherche de chemin sur la grille de combat
+//!
+//! Les combattants se déplacent case par case, horizontalement ou
+//! verticalement, et chaque pas coûte un PM. Le plus court chemin est trouvé
+//! par A*, avec la distance de Manhattan comme heuristique. Le serveur s'en
+//! sert pour valider les déplacements et le client pour les anticiper.
+
+use crate::map::GameMap;
+use crate::protocol::Position;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Cases voisines d'une position, dans un ordre fixe (haut, droite, bas, gauche)
+pub fn neighbours(position: Position) -> [Position; 4] {
+    [
+        Position::new(position.x, position.y - 1),
+        Position::new(position.x + 1, position.y),
+        Position::new(position.x, position.y + 1),
+        Position::new(position.x - 1, position.y),
+    ]
+}
+
+/// Nombre de PM nécessaires pour parcourir un chemin (position de départ incluse)
+pub fn path_cost(path: &[Position]) -> u32 {
+    path.len().saturating_sub(1) as u32
+}
+
+/// Cherche le plus court chemin de `from` à `to`
+///
+/// Le chemin retourné commence par `from` et finit par `to`. Il ne traverse
+/// que des cellules praticables pour lesquelles `is_occupied` est faux ; la
+/// case de départ n'est pas vérifiée. À longueur égale, le résultat ne dépend
+/// que de la carte et des cases occupées, pour que le combat reste rejouable.
+pub fn find_path(
+    map: &GameMap,
+    from: Position,
+    to: Position,
+    is_occupied: impl Fn(Position) -> bool,
+) -> Option<Vec<Position>> {
+    if from == to {
+        return Some(vec![from]);
+    }
+    let is_free = |position: Position| map.is_walkable(position) && !is_occupied(position);
+    if !is_free(to) {
+        return None;
+    }
+
+    // File de priorité : (coût estimé, ordre d'insertion, case), le plus petit d'abord
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<Position, Position> = HashMap::new();
+    let mut cost: HashMap<Position, u32> = HashMap::from([(from, 0)]);
+    let mut inserted = 0u64;
+    open.push(Reverse((
+        from.manhattan_distance(&to) as u32,
+        inserted,
+        from.x,
+        from.y,
+    )));
+
+    while let Some(Reverse((_, _, x, y))) = open.pop() {
+        let current = Position::new(x, y);
+        if current == to {
+            let mut path = vec![current];
+            let mut position = current;
+            while let Some(&previous) = came_from.get(&position) {
+                path.push(previous);
+                position = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let next_cost = cost[&current] + 1;
+        for next in neighbours(current) {
+            if !is_free(next) || cost.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            cost.insert(next, next_cost);
+            came_from.insert(next, current);
+            inserted += 1;
+            let estimate = next_cost + next.manhattan_distance(&to) as u32;
+            open.push(Reverse((estimate, inserted, next.x, next.y)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(layout: &str) -> GameMap {
+        let rows: Vec<&str> = layout.split_whitespace().collect();
+        GameMap::from_layout(rows[0].len() as i32, rows.len() as i32, layout).unwrap()
+    }
+
+    #[test]
+    fn test_straight_path() {
+        let map = GameMap::new(10, 10);
+        let path = find_path(&map, Position::new(2, 2), Position::new(2, 5), |_| false).unwrap();
+        assert_eq!(
+            path,
+            vec![
+                Position::new(2, 2),
+                Position::new(2, 3),
+                Position::new(2, 4),
+                Position::new(2, 5),
+            ]
+        );
+        assert_eq!(path_cost(&path), 3);
+        assert_eq!(
+            find_path(&map, Position::new(2, 2), Position::new(2, 2), |_| false),
+            Some(vec![Position::new(2, 2)])
+        );
+    }
+
+    #[test]
+    fn test_path_goes_around_obstacles_and_players() {
+        let map = map("
+            r.#..
+            ..o.b
+            .....
+            ");
+        let occupied = Position::new(1, 1);
+        let path = find_path(&map, Position::new(0, 1), Position::new(3, 1), |p| {
+            p == occupied
+        })
+        .unwrap();
+
+        assert_eq!(path_cost(&path), 5);
+        assert_eq!(path.first(), Some(&Position::new(0, 1)));
+        assert_eq!(path.last(), Some(&Position::new(3, 1)));
+        for step in path.windows(2) {
+            assert_eq!(step[0].manhattan_distance(&step[1]), 1);
+            assert!(map.is_walkable(step[1]));
+            assert_ne!(step[1], occupied);
+        }
+    }
+
+    #[test]
+    fn test_no_path() {
+        let map = map("
+            r.#..
+            ..#.b
+            ..#..
+            ");
+        // Mur infranchissable
+        assert_eq!(
+            find_path(&map, Position::new(0, 0), Position::new(4, 0), |_| false),
+            None
+        );
+        // Destination praticable mais occupée
+        assert_eq!(
+            find_path(&map, Position::new(0, 0), Position::new(1, 2), |p| {
+                p == Position::new(1, 2)
+            }),
+            None
+        );
+        // Destination hors de la carte
+        assert_eq!(
+            find_path(&map, Position::new(0, 0), Position::new(-1, 0), |_| false),
+            None
+        );
+    }
+}
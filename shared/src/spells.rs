@@ -0,0 +1,728 @@
+//! Catalogue des sorts et état des sorts connus par un personnage
+//!
+//! Les définitions des sorts sont statiques et partagées : le serveur valide les
+//! lancers avec, le client les affiche. Seul l'état propre à un personnage
+//! (relance, lancers du tour) circule sur le réseau, dans `KnownSpell`.
+
+use crate::area::AreaShape;
+use crate::effects::StatusKind;
+use crate::protocol::{Element, Position};
+use crate::summons::{self, CreatureId};
+use serde::{Deserialize, Serialize};
+
+/// Identifiant d'un sort du catalogue
+pub type SpellId = u32;
+
+/// Manière dont un sort choisit sa case cible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Targeting {
+    /// N'importe quelle case à portée
+    Free,
+    /// Uniquement sur la ligne ou la colonne du lanceur
+    Line,
+}
+
+/// Effet appliqué à chaque joueur présent dans la zone du sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpellEffect {
+    /// Dégâts élémentaires, tirés entre `min` et `max` puis modifiés par les
+    /// caractéristiques du lanceur et les résistances de la cible
+    Damage {
+        element: Element,
+        min: u32,
+        max: u32,
+    },
+    /// Rend des points de vie, sans dépasser le maximum
+    Heal { amount: u32 },
+    /// Retire des PA à la cible ; chaque point peut être esquivé
+    RemoveActionPoints { amount: u32 },
+    /// Retire des PM à la cible ; chaque point peut être esquivé
+    RemoveMovementPoints { amount: u32 },
+    /// Pose un effet temporaire sur la cible pour `duration` tours du lanceur
+    ApplyStatus {
+        status: StatusKind,
+        duration: u32,
+        dispellable: bool,
+    },
+    /// Retire les effets désenvoûtables de la cible
+    Dispel,
+    /// Repousse la cible de `distance` cases dans l'axe lanceur-cible ; si elle
+    /// heurte un obstacle ou un combattant, elle subit des dégâts de collision
+    Push { distance: u32 },
+    /// Attire la cible de `distance` cases vers le lanceur
+    Pull { distance: u32 },
+    /// Échange les positions du lanceur et de la cible
+    SwapPositions,
+    /// Téléporte le lanceur sur la case ciblée, qui doit être libre
+    Teleport,
+    /// Invoque une créature sur la case ciblée, qui doit être libre
+    Summon { creature: CreatureId },
+}
+
+impl SpellEffect {
+    /// Indique si l'effet nuit à sa cible ; il ne touche alors pas les alliés du lanceur
+    pub fn is_hostile(&self) -> bool {
+        match self {
+            SpellEffect::Damage { .. }
+            | SpellEffect::RemoveActionPoints { .. }
+            | SpellEffect::RemoveMovementPoints { .. } => true,
+            SpellEffect::Push { .. } => true,
+            SpellEffect::ApplyStatus { status, .. } => status.is_hostile(),
+            SpellEffect::Heal { .. }
+            | SpellEffect::Dispel
+            | SpellEffect::Pull { .. }
+            | SpellEffect::SwapPositions
+            | SpellEffect::Teleport
+            | SpellEffect::Summon { .. } => false,
+        }
+    }
+
+    /// Indique si l'effet s'applique à la case ciblée, libre, plutôt qu'aux
+    /// combattants de la zone
+    pub fn targets_cell(&self) -> bool {
+        matches!(self, SpellEffect::Teleport | SpellEffect::Summon { .. })
+    }
+}
+
+/// Définition d'un sort
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spell {
+    pub id: SpellId,
+    pub name: &'static str,
+    /// Coût en PA
+    pub ap_cost: u32,
+    /// Portée minimale, en cases (distance de Manhattan)
+    pub min_range: u32,
+    /// Portée maximale, en cases (distance de Manhattan)
+    pub max_range: u32,
+    pub targeting: Targeting,
+    /// Le lancer demande une ligne de vue vers la case ciblée
+    pub line_of_sight: bool,
+    /// Zone d'effet autour de la case ciblée
+    pub area: AreaShape,
+    /// Nombre maximum de lancers par tour, `None` si illimité
+    pub casts_per_turn: Option<u32>,
+    /// Nombre de tours du lanceur avant de pouvoir relancer le sort
+    pub cooldown: u32,
+    /// Probabilité de coup critique, en pourcentage
+    pub critical_chance: u32,
+    /// Dégâts de base ajoutés à chaque effet de dégâts en cas de coup critique
+    pub critical_bonus: u32,
+    pub effects: &'static [SpellEffect],
+}
+
+impl Spell {
+    /// Vérifie que `target` est dans la zone de lancer depuis `from`
+    ///
+    /// Retourne la distance de la cible si elle est à portée.
+    pub fn check_range(&self, from: Position, target: Position) -> Result<u32, RangeError> {
+        let distance = from.manhattan_distance(&target) as u32;
+        if distance > self.max_range {
+            return Err(RangeError::TooFar { distance });
+        }
+        if distance < self.min_range {
+            return Err(RangeError::TooClose { distance });
+        }
+        if self.targeting == Targeting::Line && from.x != target.x && from.y != target.y {
+            return Err(RangeError::NotInLine);
+        }
+        Ok(distance)
+    }
+}
+
+/// Raison pour laquelle une case n'est pas dans la zone de lancer d'un sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    TooFar { distance: u32 },
+    TooClose { distance: u32 },
+    NotInLine,
+}
+
+pub const PRESSION: SpellId = 1;
+pub const FLECHE_MAGIQUE: SpellId = 2;
+pub const EPEE_CELESTE: SpellId = 3;
+pub const MOT_SOIGNANT: SpellId = 4;
+pub const FLECHE_RALENTISSANTE: SpellId = 5;
+pub const FLECHE_IMMOBILISANTE: SpellId = 6;
+pub const FLECHE_EMPOISONNEE: SpellId = 7;
+pub const COMPULSION: SpellId = 8;
+pub const ARMURE_TERRESTRE: SpellId = 9;
+pub const RACINES: SpellId = 10;
+pub const DESENVOUTEMENT: SpellId = 11;
+pub const IMMUNITE: SpellId = 12;
+pub const INTIMIDATION: SpellId = 13;
+pub const ATTIRANCE: SpellId = 14;
+pub const TRANSPOSITION: SpellId = 15;
+pub const BOND: SpellId = 16;
+pub const CROIX_DE_FEU: SpellId = 17;
+pub const EXPLOSION: SpellId = 18;
+pub const SOUFFLE: SpellId = 19;
+pub const EVENTAIL: SpellId = 20;
+pub const ANNEAU_DE_RONCES: SpellId = 21;
+pub const INVOCATION_DE_BOUFTOU: SpellId = 22;
+pub const INVOCATION_DE_TOFU: SpellId = 23;
+
+/// Catalogue de tous les sorts du jeu
+pub const SPELLS: &[Spell] = &[
+    Spell {
+        id: PRESSION,
+        name: "Pression",
+        ap_cost: 3,
+        min_range: 1,
+        max_range: 1,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(2),
+        cooldown: 0,
+        critical_chance: 10,
+        critical_bonus: 5,
+        effects: &[SpellEffect::Damage {
+            element: Element::Earth,
+            min: 18,
+            max: 22,
+        }],
+    },
+    Spell {
+        id: FLECHE_MAGIQUE,
+        name: "Flèche magique",
+        ap_cost: 4,
+        min_range: 2,
+        max_range: 6,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: None,
+        cooldown: 0,
+        critical_chance: 5,
+        critical_bonus: 4,
+        effects: &[SpellEffect::Damage {
+            element: Element::Fire,
+            min: 13,
+            max: 17,
+        }],
+    },
+    Spell {
+        id: EPEE_CELESTE,
+        name: "Épée céleste",
+        ap_cost: 4,
+        min_range: 1,
+        max_range: 4,
+        targeting: Targeting::Line,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 2,
+        critical_chance: 15,
+        critical_bonus: 8,
+        effects: &[SpellEffect::Damage {
+            element: Element::Air,
+            min: 26,
+            max: 32,
+        }],
+    },
+    Spell {
+        id: MOT_SOIGNANT,
+        name: "Mot soignant",
+        ap_cost: 2,
+        min_range: 0,
+        max_range: 3,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 3,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::Heal { amount: 20 }],
+    },
+    Spell {
+        id: FLECHE_RALENTISSANTE,
+        name: "Flèche ralentissante",
+        ap_cost: 3,
+        min_range: 1,
+        max_range: 5,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(2),
+        cooldown: 0,
+        critical_chance: 5,
+        critical_bonus: 3,
+        effects: &[
+            SpellEffect::Damage {
+                element: Element::Water,
+                min: 6,
+                max: 8,
+            },
+            SpellEffect::RemoveActionPoints { amount: 2 },
+        ],
+    },
+    Spell {
+        id: FLECHE_IMMOBILISANTE,
+        name: "Flèche immobilisante",
+        ap_cost: 2,
+        min_range: 1,
+        max_range: 4,
+        targeting: Targeting::Line,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 1,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::RemoveMovementPoints { amount: 2 }],
+    },
+    Spell {
+        id: FLECHE_EMPOISONNEE,
+        name: "Flèche empoisonnée",
+        ap_cost: 3,
+        min_range: 1,
+        max_range: 6,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(2),
+        cooldown: 0,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::ApplyStatus {
+            status: StatusKind::Poison {
+                element: Element::Air,
+                amount: 8,
+            },
+            duration: 3,
+            dispellable: true,
+        }],
+    },
+    Spell {
+        id: COMPULSION,
+        name: "Compulsion",
+        ap_cost: 2,
+        min_range: 0,
+        max_range: 0,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 4,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[
+            SpellEffect::ApplyStatus {
+                status: StatusKind::DamageBoost { percent: 20 },
+                duration: 2,
+                dispellable: true,
+            },
+            SpellEffect::ApplyStatus {
+                status: StatusKind::ActionPoints { amount: 1 },
+                duration: 2,
+                dispellable: true,
+            },
+        ],
+    },
+    Spell {
+        id: ARMURE_TERRESTRE,
+        name: "Armure terrestre",
+        ap_cost: 3,
+        min_range: 0,
+        max_range: 3,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 4,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[
+            SpellEffect::ApplyStatus {
+                status: StatusKind::Shield { amount: 30 },
+                duration: 2,
+                dispellable: true,
+            },
+            SpellEffect::ApplyStatus {
+                status: StatusKind::Resistance {
+                    element: Element::Earth,
+                    percent: 20,
+                },
+                duration: 2,
+                dispellable: true,
+            },
+        ],
+    },
+    Spell {
+        id: RACINES,
+        name: "Racines",
+        ap_cost: 3,
+        min_range: 1,
+        max_range: 4,
+        targeting: Targeting::Line,
+        line_of_sight: false,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 3,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::ApplyStatus {
+            status: StatusKind::Rooted,
+            duration: 1,
+            dispellable: true,
+        }],
+    },
+    Spell {
+        id: DESENVOUTEMENT,
+        name: "Désenvoûtement",
+        ap_cost: 2,
+        min_range: 1,
+        max_range: 6,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 2,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::Dispel],
+    },
+    Spell {
+        id: IMMUNITE,
+        name: "Immunité",
+        ap_cost: 2,
+        min_range: 0,
+        max_range: 0,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 6,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::ApplyStatus {
+            status: StatusKind::Invulnerable,
+            duration: 1,
+            dispellable: false,
+        }],
+    },
+    Spell {
+        id: INTIMIDATION,
+        name: "Intimidation",
+        ap_cost: 2,
+        min_range: 1,
+        max_range: 1,
+        targeting: Targeting::Line,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(2),
+        cooldown: 0,
+        critical_chance: 5,
+        critical_bonus: 2,
+        effects: &[
+            SpellEffect::Damage {
+                element: Element::Earth,
+                min: 8,
+                max: 10,
+            },
+            SpellEffect::Push { distance: 3 },
+        ],
+    },
+    Spell {
+        id: ATTIRANCE,
+        name: "Attirance",
+        ap_cost: 2,
+        min_range: 2,
+        max_range: 5,
+        targeting: Targeting::Line,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 0,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::Pull { distance: 3 }],
+    },
+    Spell {
+        id: TRANSPOSITION,
+        name: "Transposition",
+        ap_cost: 1,
+        min_range: 1,
+        max_range: 6,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 2,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::SwapPositions],
+    },
+    Spell {
+        id: BOND,
+        name: "Bond",
+        ap_cost: 5,
+        min_range: 1,
+        max_range: 4,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 2,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::Teleport],
+    },
+    Spell {
+        id: CROIX_DE_FEU,
+        name: "Croix de feu",
+        ap_cost: 4,
+        min_range: 2,
+        max_range: 5,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Cross { size: 1 },
+        casts_per_turn: Some(2),
+        cooldown: 0,
+        critical_chance: 5,
+        critical_bonus: 3,
+        effects: &[SpellEffect::Damage {
+            element: Element::Fire,
+            min: 10,
+            max: 12,
+        }],
+    },
+    Spell {
+        id: EXPLOSION,
+        name: "Explosion",
+        ap_cost: 4,
+        min_range: 2,
+        max_range: 6,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Circle { size: 1 },
+        casts_per_turn: Some(1),
+        cooldown: 1,
+        critical_chance: 5,
+        critical_bonus: 3,
+        effects: &[SpellEffect::Damage {
+            element: Element::Water,
+            min: 9,
+            max: 11,
+        }],
+    },
+    Spell {
+        id: SOUFFLE,
+        name: "Souffle",
+        ap_cost: 3,
+        min_range: 1,
+        max_range: 3,
+        targeting: Targeting::Line,
+        line_of_sight: true,
+        area: AreaShape::Line { size: 2 },
+        casts_per_turn: Some(2),
+        cooldown: 0,
+        critical_chance: 5,
+        critical_bonus: 2,
+        effects: &[SpellEffect::Damage {
+            element: Element::Air,
+            min: 8,
+            max: 10,
+        }],
+    },
+    Spell {
+        id: EVENTAIL,
+        name: "Éventail",
+        ap_cost: 3,
+        min_range: 1,
+        max_range: 1,
+        targeting: Targeting::Line,
+        line_of_sight: true,
+        area: AreaShape::Cone { size: 2 },
+        casts_per_turn: Some(1),
+        cooldown: 1,
+        critical_chance: 5,
+        critical_bonus: 2,
+        effects: &[SpellEffect::Damage {
+            element: Element::Air,
+            min: 6,
+            max: 8,
+        }],
+    },
+    Spell {
+        id: ANNEAU_DE_RONCES,
+        name: "Anneau de ronces",
+        ap_cost: 4,
+        min_range: 0,
+        max_range: 3,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Ring { size: 1 },
+        casts_per_turn: Some(1),
+        cooldown: 2,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::Damage {
+            element: Element::Earth,
+            min: 12,
+            max: 14,
+        }],
+    },
+    Spell {
+        id: INVOCATION_DE_BOUFTOU,
+        name: "Invocation de Bouftou",
+        ap_cost: 4,
+        min_range: 1,
+        max_range: 2,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 4,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::Summon {
+            creature: summons::BOUFTOU,
+        }],
+    },
+    Spell {
+        id: INVOCATION_DE_TOFU,
+        name: "Invocation de Tofu",
+        ap_cost: 3,
+        min_range: 1,
+        max_range: 3,
+        targeting: Targeting::Free,
+        line_of_sight: true,
+        area: AreaShape::Single,
+        casts_per_turn: Some(1),
+        cooldown: 3,
+        critical_chance: 0,
+        critical_bonus: 0,
+        effects: &[SpellEffect::Summon {
+            creature: summons::TOFU,
+        }],
+    },
+];
+
+/// Sorts connus par un personnage à sa création
+pub const STARTING_SPELLS: &[SpellId] = &[
+    PRESSION,
+    FLECHE_MAGIQUE,
+    EPEE_CELESTE,
+    MOT_SOIGNANT,
+    FLECHE_RALENTISSANTE,
+    FLECHE_IMMOBILISANTE,
+    FLECHE_EMPOISONNEE,
+    COMPULSION,
+    ARMURE_TERRESTRE,
+    RACINES,
+    DESENVOUTEMENT,
+    IMMUNITE,
+    INTIMIDATION,
+    ATTIRANCE,
+    TRANSPOSITION,
+    BOND,
+    CROIX_DE_FEU,
+    EXPLOSION,
+    SOUFFLE,
+    EVENTAIL,
+    ANNEAU_DE_RONCES,
+    INVOCATION_DE_BOUFTOU,
+    INVOCATION_DE_TOFU,
+];
+
+/// Trouve un sort du catalogue par son ID
+pub fn get(spell_id: SpellId) -> Option<&'static Spell> {
+    SPELLS.iter().find(|spell| spell.id == spell_id)
+}
+
+/// État d'un sort connu par un personnage
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KnownSpell {
+    pub spell_id: SpellId,
+    /// Tours du lanceur restant avant de pouvoir relancer le sort
+    pub cooldown_remaining: u32,
+    /// Lancers effectués pendant le tour en cours
+    pub casts_this_turn: u32,
+}
+
+impl KnownSpell {
+    pub fn new(spell_id: SpellId) -> Self {
+        Self {
+            spell_id,
+            cooldown_remaining: 0,
+            casts_this_turn: 0,
+        }
+    }
+
+    /// Enregistre un lancer du sort
+    pub fn record_cast(&mut self, spell: &Spell) {
+        self.casts_this_turn += 1;
+        self.cooldown_remaining = spell.cooldown;
+    }
+
+    /// Met à jour la relance et les lancers au début d'un tour du lanceur
+    pub fn start_turn(&mut self) {
+        self.casts_this_turn = 0;
+        self.cooldown_remaining = self.cooldown_remaining.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_ids_are_unique() {
+        for spell in SPELLS {
+            assert_eq!(get(spell.id), Some(spell));
+        }
+        assert!(get(0).is_none());
+        for spell_id in STARTING_SPELLS {
+            assert!(get(*spell_id).is_some());
+        }
+    }
+
+    #[test]
+    fn test_check_range() {
+        let spell = get(FLECHE_MAGIQUE).unwrap();
+        let from = Position::new(5, 5);
+
+        assert_eq!(spell.check_range(from, Position::new(6, 6)), Ok(2));
+        assert_eq!(
+            spell.check_range(from, Position::new(6, 5)),
+            Err(RangeError::TooClose { distance: 1 })
+        );
+        assert_eq!(
+            spell.check_range(from, Position::new(9, 8)),
+            Err(RangeError::TooFar { distance: 7 })
+        );
+    }
+
+    #[test]
+    fn test_line_targeting() {
+        let spell = get(EPEE_CELESTE).unwrap();
+        let from = Position::new(5, 5);
+
+        assert_eq!(spell.check_range(from, Position::new(5, 8)), Ok(3));
+        assert_eq!(spell.check_range(from, Position::new(2, 5)), Ok(3));
+        assert_eq!(
+            spell.check_range(from, Position::new(6, 6)),
+            Err(RangeError::NotInLine)
+        );
+    }
+
+    #[test]
+    fn test_cooldown_counts_caster_turns() {
+        let spell = get(EPEE_CELESTE).unwrap();
+        let mut known = KnownSpell::new(spell.id);
+
+        known.record_cast(spell);
+        assert_eq!(known.casts_this_turn, 1);
+        assert_eq!(known.cooldown_remaining, 2);
+
+        known.start_turn();
+        assert_eq!(known.casts_this_turn, 0);
+        assert_eq!(known.cooldown_remaining, 1);
+
+        known.start_turn();
+        assert_eq!(known.cooldown_remaining, 0);
+    }
+}
@@ -0,0 +1,99 @@
+//! Invocations : créatures appelées en combat par un sort
+//!
+//! Une invocation est un combattant à part entière (`PlayerState`) de l'équipe
+//! de son invocateur, avec les caractéristiques et les sorts de sa créature.
+//! Elle joue juste après son invocateur dans la frise et meurt avec lui. Selon
+//! la créature, elle est dirigée par le joueur qui l'a invoquée ou par le
+//! serveur.
+
+use crate::protocol::{PlayerId, Stats};
+use crate::spells::{self, SpellId};
+use serde::{Deserialize, Serialize};
+
+/// Identifiant d'une créature du catalogue
+pub type CreatureId = u32;
+
+/// Qui dirige une invocation pendant son tour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Le joueur qui l'a invoquée, comme son propre personnage
+    Summoner,
+    /// Le serveur, qui la fait jouer seule
+    Server,
+}
+
+/// Définition d'une créature invocable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Creature {
+    pub id: CreatureId,
+    pub name: &'static str,
+    pub stats: Stats,
+    /// Sorts connus par la créature, dans l'ordre de sa barre de sorts
+    pub spells: &'static [SpellId],
+    pub control: Control,
+}
+
+/// Lien d'une invocation avec son invocateur
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summon {
+    pub summoner_id: PlayerId,
+    pub creature_id: CreatureId,
+}
+
+pub const BOUFTOU: CreatureId = 1;
+pub const TOFU: CreatureId = 2;
+
+/// Catalogue de toutes les créatures invocables
+pub const CREATURES: &[Creature] = &[
+    Creature {
+        id: BOUFTOU,
+        name: "Bouftou",
+        stats: Stats {
+            strength: 20,
+            intelligence: 0,
+            chance: 0,
+            agility: 0,
+            vitality: -40,
+            wisdom: 0,
+        },
+        spells: &[spells::PRESSION],
+        control: Control::Server,
+    },
+    Creature {
+        id: TOFU,
+        name: "Tofu",
+        stats: Stats {
+            strength: 0,
+            intelligence: 0,
+            chance: 0,
+            agility: 40,
+            vitality: -70,
+            wisdom: 0,
+        },
+        spells: &[spells::SOUFFLE, spells::INTIMIDATION],
+        control: Control::Summoner,
+    },
+];
+
+/// Trouve une créature du catalogue par son ID
+pub fn get(creature_id: CreatureId) -> Option<&'static Creature> {
+    CREATURES.iter().find(|creature| creature.id == creature_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_creatures_are_valid() {
+        for creature in CREATURES {
+            assert_eq!(get(creature.id), Some(creature));
+            assert!(!creature.spells.is_empty());
+            assert!(creature
+                .spells
+                .iter()
+                .all(|&spell_id| spells::get(spell_id).is_some()));
+        }
+        assert!(get(0).is_none());
+    }
+}